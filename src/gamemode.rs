@@ -5,15 +5,32 @@ use agb::display::object::{AffineMatrixInstance, AffineMode, Graphics, OamUnmana
 use agbrs_flash::FlashMemory;
 use agb::input::{Button, ButtonController};
 use agb::display::Priority;
-use agb::display::tiled::RegularBackgroundSize::Background32x32;
+use agb::save::SaveManager;
+use agb::display::tiled::RegularBackgroundSize::{Background32x32, Background64x64};
 use agb::fixnum::{Num, Vector2D};
+use rand::Rng;
+use crate::state::bridge::{salvage, BRIDGE_DURABILITY, STONE_BRIDGE};
+use crate::state::building::{Building, MAX_FOOTPRINT};
+use crate::state::campfire::Campfire;
+use crate::state::crafting::RECIPES;
+use crate::state::daytime::{SpritePalette, TimeOfDay, Tint};
+use crate::state::drift::{Drifting, MAX_DRIFTING};
+use crate::state::fishing::{FishingOutcome, FishingState, REEL_GAUGE_MAX, REEL_PULLS_TO_LAND};
 use crate::state::gamestate::GameState;
+use crate::state::highscore::{HighScores, HIGH_SCORE_COUNT};
+use crate::state::placeable::Placeable;
+use crate::state::rock::RockKind;
 use crate::state::player::Direction;
-use crate::state::serialized::SerializedState;
+use crate::state::serialized::{SerializedState, SAVE_VERSION};
 use crate::state::inventory::ItemType;
+use crate::state::terrain::Terrain;
+use crate::state::trap::{Trap, TrapKind, MAX_TRAPS};
+use crate::state::tree::GrowthStage;
+use crate::state::weather::Weather;
+use crate::state::worldgen::{WorldGenParams, WorldPreset, SEED_MASK};
 use crate::traits::{Digits, NextTo};
 
-agb::include_background_gfx!(pub background, "d77bba", tiles256 => 256 "map.aseprite", tiles16 => 16 "map.aseprite", font => "font.png");
+agb::include_background_gfx!(pub background, "d77bba", tiles256 => 256 "map.aseprite", tiles16 => 16 "map.aseprite", shore => 16 "shore.aseprite", buildings => 16 "buildings.aseprite", font => "font.png");
 
 pub struct GameMode<'a, 'b> {
     vram: &'b mut VRamManager,
@@ -22,111 +39,213 @@ pub struct GameMode<'a, 'b> {
     button_controller: ButtonController,
     background: MapLoan<'a, RegularMap>,
     menumap: MapLoan<'a, RegularMap>,
-    tree_obj: [ObjectUnmanaged; TREECOUNT],
+    tree_obj: Vec<ObjectUnmanaged>,
+    tree_sprites: Vec<SpriteVram>,
+    rock_obj: Vec<ObjectUnmanaged>,
+    rock_sprites: Vec<SpriteVram>,
+    trap_obj: [ObjectUnmanaged; MAX_TRAPS],
+    trap_sprites: Vec<SpriteVram>,
+    drift_obj: [ObjectUnmanaged; MAX_DRIFTING],
+    drift_sprites: Vec<SpriteVram>,
     memory: FlashMemory,
+    save: &'b mut SaveManager,
+    high_scores: HighScores,
     player_obj: ObjectUnmanaged,
     player_sprites: Vec<SpriteVram>,
+    bobber_obj: ObjectUnmanaged,
+    bobber_sprites: Vec<SpriteVram>,
+    campfire_obj: ObjectUnmanaged,
+    campfire_sprites: Vec<SpriteVram>,
+    weather_obj: [ObjectUnmanaged; WEATHER_PARTICLES],
+    weather_sprites: Vec<SpriteVram>,
+    ghost_obj: [ObjectUnmanaged; MAX_FOOTPRINT],
+    ghost_sprites: Vec<SpriteVram>,
+    crafting_cursor: Option<usize>,
+    move_cooldown: u8,
+    placing: Placeable,
+    sprite_palette: SpritePalette,
+    tint: Tint,
+    /// Whether the river was last drawn frozen, to redraw it when the season freezes or thaws it.
+    frozen: bool,
+    /// Outcome of the last cast and the frames it's still shown for.
+    fishing_outcome: Option<(FishingOutcome, u8)>,
 }
 
-const FRAMES_PER_STEP: usize = 35;
-const TREE_LIFETIME_IN_STEPS: i8 = 45;
+pub const FRAMES_PER_STEP: usize = 35;
+const FRUIT_REGROW_IN_STEPS: u8 = 60;
+const WEAK_MOVE_COOLDOWN: u8 = 12;
+const TREE_POINTS: u32 = 2;
+const ROCK_POINTS: u32 = 3;
+/// Extra planks a stone axe gets out of every standing tree.
+const STONE_AXE_BONUS: u8 = 1;
+const BRIDGE_POINTS: u32 = 5;
+const WEATHER_PARTICLES: usize = 8;
+/// Frames the outcome of a cast stays on screen.
+const FISHING_OUTCOME_FRAMES: u8 = 90;
+const OAM_SLOTS: usize = 128;
+/// Sprites for the rocks on screen, there are fewer of them than there are trees.
+const ROCK_SPRITES: usize = 16;
+/// Sprites left for trees once the player, bobber, campfire, building preview, rocks, traps, drifting things and
+/// weather have theirs.
+const TREE_SPRITES: usize = OAM_SLOTS - 3 - MAX_FOOTPRINT - ROCK_SPRITES - MAX_TRAPS - MAX_DRIFTING - WEATHER_PARTICLES;
 
 impl<'a, 'b> GameMode<'a, 'b>
 where
     'b: 'a,
 {
-    pub fn new(tiled: &'b Tiled0<'a>, vram: &'b mut VRamManager, oam: &'b mut OamUnmanaged<'a>, spriteloader: &'b mut SpriteLoader, mut memory: FlashMemory, reload_state: bool, seed: u64) -> Self {
+    pub fn new(tiled: &'b Tiled0<'a>, vram: &'b mut VRamManager, oam: &'b mut OamUnmanaged<'a>, spriteloader: &'b mut SpriteLoader, mut memory: FlashMemory, save: &'b mut SaveManager, reload_state: bool, seed: u64, params: WorldGenParams) -> Self {
         let button_controller = ButtonController::new();
+        let high_scores = HighScores::load(save);
         let mut state = if reload_state {
-            if let Some(state) = memory.read_structure::<SerializedState>() {
+            if let Some(state) = SerializedState::load(&mut memory) {
                 GameState::from_save_state(state)
             } else {
-                GameState::new(seed)
+                GameState::new(seed, params)
             }
         } else {
-            GameState::new(seed)
+            GameState::new(seed, params)
         };
-        let mut background = tiled.background(Priority::P3, Background32x32, TileFormat::FourBpp);
+        let mut background = tiled.background(Priority::P3, Background64x64, TileFormat::FourBpp);
         state.upload(vram, &mut background);
-        let tree_sprite = spriteloader.get_vram_sprite(&TREE_SPRITE.sprites()[0]);
-        let tree_obj = state.map_data().get_tree_positions().map(|_| {
-            ObjectUnmanaged::new(tree_sprite.clone())
-        });
+        let frozen = state.season().is_frozen();
+        let tree_sprites = TREE_SPRITE.sprites().iter().map(|sprite| spriteloader.get_vram_sprite(sprite)).collect::<Vec<_>>();
+        let tree_obj = Vec::with_capacity(TREE_SPRITES);
+        let rock_sprites = ROCK_SPRITE.sprites().iter().map(|sprite| spriteloader.get_vram_sprite(sprite)).collect::<Vec<_>>();
+        let rock_obj = Vec::with_capacity(ROCK_SPRITES);
+        let trap_sprites = TRAP_SPRITE.sprites().iter().map(|sprite| spriteloader.get_vram_sprite(sprite)).collect::<Vec<_>>();
+        let trap_obj = [(); MAX_TRAPS].map(|_| ObjectUnmanaged::new(trap_sprites[0].clone()));
+        let drift_sprites = DRIFT_SPRITE.sprites().iter().map(|sprite| spriteloader.get_vram_sprite(sprite)).collect::<Vec<_>>();
+        let drift_obj = [(); MAX_DRIFTING].map(|_| ObjectUnmanaged::new(drift_sprites[0].clone()));
         let player_sprites = MAN_SPRITE.sprites().iter().map(|sprite| spriteloader.get_vram_sprite(sprite)).collect::<Vec<_>>();
         let player_obj = ObjectUnmanaged::new(player_sprites[0].clone());
+        let bobber_sprites = BOBBER_SPRITE.sprites().iter().map(|sprite| spriteloader.get_vram_sprite(sprite)).collect::<Vec<_>>();
+        let bobber_obj = ObjectUnmanaged::new(bobber_sprites[0].clone());
+        let campfire_sprites = CAMPFIRE_SPRITE.sprites().iter().map(|sprite| spriteloader.get_vram_sprite(sprite)).collect::<Vec<_>>();
+        let campfire_obj = ObjectUnmanaged::new(campfire_sprites[0].clone());
+        let weather_sprites = WEATHER_SPRITE.sprites().iter().map(|sprite| spriteloader.get_vram_sprite(sprite)).collect::<Vec<_>>();
+        let weather_obj = [(); WEATHER_PARTICLES].map(|_| ObjectUnmanaged::new(weather_sprites[0].clone()));
+        let ghost_sprites = GHOST_SPRITE.sprites().iter().map(|sprite| spriteloader.get_vram_sprite(sprite)).collect::<Vec<_>>();
+        let ghost_obj = [(); MAX_FOOTPRINT].map(|_| ObjectUnmanaged::new(ghost_sprites[0].clone()));
         let menumap = tiled.background(Priority::P0, Background32x32, TileFormat::FourBpp);
-        let game = Self { vram, state, oam, button_controller, background, tree_obj, menumap, memory, player_obj, player_sprites };
+        let sprite_palette = SpritePalette::default();
+        let game = Self { vram, state, oam, button_controller, background, tree_obj, tree_sprites, rock_obj, rock_sprites, trap_obj, trap_sprites, drift_obj, drift_sprites, menumap, memory, save, high_scores, player_obj, player_sprites, bobber_obj, bobber_sprites, campfire_obj, campfire_sprites, weather_obj, weather_sprites, ghost_obj, ghost_sprites, crafting_cursor: None, move_cooldown: 0, placing: Placeable::Bridge, sprite_palette, tint: Tint::NONE, frozen, fishing_outcome: None };
         game
     }
 
     pub fn step(&mut self) {
         self.button_controller.update();
-        let mut movement_pressed = None;
-        for (button, direction) in [Button::UP, Button::DOWN, Button::LEFT, Button::RIGHT].into_iter().zip([Direction::UP, Direction::DOWN, Direction::LEFT, Direction::RIGHT].into_iter()) {
-            if self.button_controller.is_pressed(button) {
-                self.state.player_obj_mut().set_direction(direction);
-                if self.button_controller.is_just_pressed(button) { movement_pressed = Some(direction); }
+        if self.state.hunger().is_starved() {
+            self.step_game_over();
+        } else if let Some(cursor) = self.crafting_cursor {
+            self.step_crafting(cursor);
+        } else if self.state.fishing().is_active() {
+            self.step_fishing();
+        } else {
+            self.step_walking();
+            self.state.stream(self.vram, &mut self.background);
+        }
+
+        let scroll_cols = self.scroll_cols();
+        let scroll_rows = self.scroll_rows();
+        // only the trees on screen get a sprite, there can be more trees on the map than there are sprites
+        self.tree_obj.clear();
+        for (index, tree) in self.state.map_data().get_trees().iter().enumerate() {
+            let Some(tree) = tree else {
+                continue;
+            };
+            let (x, y) = tree.position;
+            let px = (x as i32 - scroll_cols) * 8;
+            let py = ((y as i32 - scroll_rows) * 8) - 8;
+            if !Self::on_screen(px, py) {
+                continue;
+            }
+            if self.tree_obj.len() == TREE_SPRITES {
                 break;
             }
+            let frame = tree.stage.frame(self.state.map_data().has_fruit(index));
+            let mut obj = ObjectUnmanaged::new(self.tree_sprites[frame].clone());
+            obj.set_position(Vector2D::new(px, py));
+            obj.set_priority(Priority::P1);
+            obj.show();
+            self.tree_obj.push(obj);
         }
-        if let Some(dir) = movement_pressed {
-            let (nx, ny) = self.state.player_obj().move_direction(dir);
-            let is_walkable = !(self.state.map_data().get_terrain_point((nx as usize, ny as usize)) && !self.state.map_data().get_bridge_point((nx as usize, ny as usize)));
-            let collides_tree = self.state.map_data().has_tree((nx as u16, ny as u16));
-            if is_walkable && !collides_tree {
-                self.state.player_obj_mut().set_position((nx, ny));
+        self.rock_obj.clear();
+        for rock in self.state.map_data().rocks.iter().flatten() {
+            let (x, y) = rock.position;
+            let px = (x as i32 - scroll_cols) * 8;
+            let py = (y as i32 - scroll_rows) * 8;
+            if !Self::on_screen(px, py) {
+                continue;
+            }
+            if self.rock_obj.len() == ROCK_SPRITES {
+                break;
             }
+            let mut obj = ObjectUnmanaged::new(self.rock_sprites[rock.frame()].clone());
+            obj.set_position(Vector2D::new(px, py));
+            obj.set_priority(Priority::P1);
+            obj.show();
+            self.rock_obj.push(obj);
         }
-
-        for (_index, (tree, obj)) in self.state.map_data().get_tree_positions().iter().zip(self.tree_obj.iter_mut()).enumerate() {
-            let (x, y, timeout) = *tree;
-            let px = x as i32 * 8;
-            let y = (y as i16) - (if self.state.player_obj().get_position().1 as u16 > Y_SCROLL_THRESHOLD as u16 {
-                self.state.player_obj().get_position().1 as i16 - Y_SCROLL_THRESHOLD as i16
-            } else { 0 }).min((MAPHEIGHT as i16) - 20);
-            let py = (y as i32 * 8) - 8;
+        for (trap, obj) in self.state.map_data().traps.iter().zip(self.trap_obj.iter_mut()) {
+            let Some(trap) = trap else {
+                obj.hide();
+                continue;
+            };
+            let (x, y) = trap.position;
+            let py = (y as i32 - scroll_rows) * 8;
+            let frame = match (trap.kind, trap.fish > 0) {
+                (TrapKind::FishTrap, false) => 0,
+                (TrapKind::FishTrap, true) => 1,
+                (TrapKind::Net, false) => 2,
+                (TrapKind::Net, true) => 3,
+            };
+            let px = (x as i32 - scroll_cols) * 8;
+            obj.set_sprite(self.trap_sprites[frame].clone());
             obj.set_position(Vector2D::new(px, py));
             obj.set_priority(Priority::P1);
-            if timeout == 0 && py < 128 {
+            if Self::on_screen(px, py) {
                 obj.show();
             } else {
                 obj.hide();
             }
         }
-        if self.button_controller.is_just_pressed(Button::A) {
-            let (px, py) = self.state.player_obj().get_position();
-            let (px, py) = (px as u16, py as u16);
-            let mut found_wood = 0;
-            for tree in self.state.map_data_mut().get_tree_positions_mut().iter_mut() {
-                let (tx, ty, timeout) = *tree;
-                if (tx, ty).is_next_to(&(px, py)) && timeout == 0 {
-                    tree.2 = TREE_LIFETIME_IN_STEPS;
-                    found_wood += 3;
-                }
+        for (drifting, obj) in self.state.map_data().drifting.iter().zip(self.drift_obj.iter_mut()) {
+            let Some(drifting) = drifting else {
+                obj.hide();
+                continue;
+            };
+            let (x, y) = drifting.position;
+            let px = (x as i32 - scroll_cols) * 8;
+            let py = (y as i32 - scroll_rows) * 8;
+            obj.set_sprite(self.drift_sprites[drifting.frame()].clone());
+            obj.set_position(Vector2D::new(px, py));
+            obj.set_priority(Priority::P1);
+            if Self::on_screen(px, py) {
+                obj.show();
+            } else {
+                obj.hide();
             }
-            *self.state.inventory_mut().item_mut(ItemType::WoodPlank) += found_wood;
         }
-
-        if self.button_controller.is_just_pressed(Button::B) {
-            let (px, py) = self.state.player_obj().get_position();
-            let (px, py) = (px as u16, py as u16);
-            let direction = self.state.player_obj().get_direction();
-            let (tx, ty) = match direction {
-                Direction::UP => (px, py.wrapping_sub(1)),
-                Direction::DOWN => (px, py + 1),
-                Direction::LEFT => (px.wrapping_sub(1), py),
-                Direction::RIGHT => (px + 1, py),
-            };
-            if !(tx >= 32 || ty >= 32) {
-                let point = (tx as usize, ty as usize);
-                if self.state.map_data().get_terrain_point(point) && !self.state.map_data().get_bridge_point(point) && *self.state.inventory().item(ItemType::WoodPlank) > 0 {
-                    *self.state.inventory_mut().item_mut(ItemType::WoodPlank) -= 1;
-                    self.state.map_data_mut().set_bridge_point(point, true);
-                    self.state.upload(self.vram, &mut self.background);
-                }
+        if let Some(campfire) = self.state.map_data().campfire {
+            let (x, y) = campfire.position;
+            let py = (y as i32 - scroll_rows) * 8;
+            let frame = if campfire.is_lit() { (self.state.frame() / (FRAME_SCALE * 2)) % 2 } else { 2 };
+            self.campfire_obj.set_sprite(self.campfire_sprites[frame].clone());
+            let px = (x as i32 - scroll_cols) * 8;
+            self.campfire_obj.set_position(Vector2D::new(px, py));
+            self.campfire_obj.set_priority(Priority::P1);
+            if Self::on_screen(px, py) {
+                self.campfire_obj.show();
+            } else {
+                self.campfire_obj.hide();
             }
+        } else {
+            self.campfire_obj.hide();
         }
+        self.step_ghost(scroll_cols, scroll_rows);
         let current_frame = self.state.step_frame();
+        self.step_weather_particles(current_frame);
+        self.fishing_outcome = self.fishing_outcome.and_then(|(outcome, frames)| frames.checked_sub(1).map(|frames| (outcome, frames)));
         self.player_obj.set_sprite(self.player_sprites[(current_frame / FRAME_SCALE) % self.player_sprites.len()].clone());
 
         let angle: Num<i32, 8> = agb::fixnum::num!(0.25) * match self.state.player_obj().get_direction() {
@@ -138,45 +257,576 @@ where
         let matrix = AffineMatrix::from_rotation(angle);
         self.player_obj.set_affine_matrix(AffineMatrixInstance::new(matrix.to_object_wrapping()));
         let (ox, oy) = self.state.player_obj().get_position();
-        let (ox, oy) = (ox as i32, oy as i32);
-        let oy = oy - (if oy > Y_SCROLL_THRESHOLD {
-            oy - Y_SCROLL_THRESHOLD
-        } else { 0 }).min((MAPHEIGHT as i32) - 20);
+        let (ox, oy) = (ox as i32 - scroll_cols, oy as i32 - scroll_rows);
         let pos: Vector2D<i32> = Vector2D::new(ox * 8 - 4, oy * 8 - 4);
         self.player_obj.set_position(pos);
         self.player_obj.set_priority(Priority::P2);
         self.player_obj.show_affine(AffineMode::Affine);
 
+        if let Some((tx, ty)) = self.state.fishing().target() {
+            let sprite = match self.state.fishing() {
+                FishingState::Bite { .. } => (current_frame / FRAME_SCALE) % self.bobber_sprites.len(),
+                FishingState::Reeling { .. } => 1,
+                _ => 0,
+            };
+            self.bobber_obj.set_sprite(self.bobber_sprites[sprite].clone());
+            self.bobber_obj.set_position(Vector2D::new((tx as i32 - scroll_cols) * 8, (ty as i32 - scroll_rows) * 8));
+            self.bobber_obj.set_priority(Priority::P1);
+            self.bobber_obj.show();
+        } else {
+            self.bobber_obj.hide();
+        }
+
         if current_frame % FRAMES_PER_STEP == 0 {
-            for tree in self.state.map_data_mut().get_tree_positions_mut().iter_mut() {
-                let (_, _, timeout) = *tree;
-                if timeout > 0 {
-                    tree.2 = tree.2.saturating_sub(1);
-                }
+            if self.state.clock().1 == 0 {
+                self.change_weather();
+            }
+            self.apply_tint();
+            let was_starved = self.state.hunger().is_starved();
+            self.state.hunger_mut().tick();
+            if !was_starved && self.state.hunger().is_starved() {
+                self.high_scores.insert(self.state.score());
+                self.high_scores.store(self.save);
             }
+            for stock in self.state.map_data_mut().fish_stock.iter_mut() {
+                stock.tick();
+            }
+            let frozen = self.state.season().is_frozen();
+            self.state.map_data_mut().tick_traps(frozen);
+            if let Some(campfire) = self.state.map_data_mut().campfire.as_mut() {
+                campfire.tick();
+            }
+            self.state.tick_trees();
+            self.state.spread_trees();
+            self.state.tick_rocks();
+            self.state.weather_bridges(self.vram, &mut self.background);
+            self.state.drift();
         }
 
-        if current_frame % 60 * 10 == 0 {
+        if current_frame % (60 * 10) == 0 {
             let (px, py) = self.state.player_obj().get_position();
             let serialized = SerializedState {
+                version: SAVE_VERSION,
                 player: (px, py, self.state.player_obj().get_direction()),
                 inventory: *(self.state.inventory()),
-                map_data: *(self.state.map_data()),
+                hunger: *(self.state.hunger()),
+                score: self.state.score(),
+                weather: self.state.weather(),
+                world: self.state.map_data().to_save(),
                 frame: current_frame,
             };
-            self.memory.write_structure(&serialized);
+            serialized.store(&mut self.memory);
+        }
+    }
+
+    fn step_walking(&mut self) {
+        if self.button_controller.is_just_pressed(Button::START) {
+            self.crafting_cursor = Some(0);
+            return;
+        }
+        if self.button_controller.is_just_pressed(Button::SELECT) {
+            self.state.eat();
+        }
+        if self.button_controller.is_just_pressed(Button::R) {
+            self.placing = self.placing.next();
+        }
+        self.move_cooldown = self.move_cooldown.saturating_sub(1);
+        let mut movement_pressed = None;
+        for (button, direction) in [Button::UP, Button::DOWN, Button::LEFT, Button::RIGHT].into_iter().zip([Direction::UP, Direction::DOWN, Direction::LEFT, Direction::RIGHT].into_iter()) {
+            if self.button_controller.is_pressed(button) {
+                self.state.player_obj_mut().set_direction(direction);
+                if self.button_controller.is_just_pressed(button) && self.move_cooldown == 0 { movement_pressed = Some(direction); }
+                break;
+            }
+        }
+        if let Some(dir) = movement_pressed {
+            let (nx, ny) = self.state.player_obj().move_direction(dir);
+            let point = (nx as usize, ny as usize);
+            if let Some(cooldown) = self.state.walk_cooldown(point) {
+                self.state.player_obj_mut().set_position((nx, ny));
+                self.move_cooldown = cooldown;
+                if self.state.hunger().is_weak() {
+                    self.move_cooldown = self.move_cooldown.max(WEAK_MOVE_COOLDOWN);
+                }
+                self.state.walk_on(point, self.vram, &mut self.background);
+            }
+        }
+
+        if self.button_controller.is_just_pressed(Button::A) {
+            if self.cook() {
+                return;
+            }
+            if let Some(target) = self.facing_tile() {
+                if let Some(index) = self.state.map_data().drifting_at(target) {
+                    self.collect_drifting(index);
+                    return;
+                }
+                if let Some(index) = self.state.map_data().trap_at(target) {
+                    self.collect_trap(index);
+                    return;
+                }
+                if let Some(index) = self.state.map_data().rock_at(target) {
+                    if self.mine(index) {
+                        return;
+                    }
+                }
+                let point = (target.0 as usize, target.1 as usize);
+                if self.quarry(point) {
+                    return;
+                }
+                let has_rod = *self.state.inventory().item(ItemType::FishingRod) > 0;
+                let terrain = self.state.map_data().get_terrain(point);
+                let is_open_water = terrain.is_water() && !self.state.map_data().get_bridge_point(point) && self.state.map_data().building_at(target).is_none();
+                if has_rod && is_open_water {
+                    let time_of_day = self.state.time_of_day();
+                    let season = self.state.season();
+                    let stock = *self.state.map_data().fish_stock(target);
+                    let (fishing, rng) = self.state.fishing_mut();
+                    fishing.cast(target, &stock, time_of_day, season, terrain, rng);
+                    self.fishing_outcome = None;
+                    return;
+                }
+            }
+            if self.pick_fruit() {
+                return;
+            }
+            let (px, py) = self.state.player_obj().get_position();
+            let (px, py) = (px as u16, py as u16);
+            let stone_axe = *self.state.inventory().item(ItemType::StoneAxe) > 0;
+            let axe = stone_axe || *self.state.inventory().item(ItemType::Axe) > 0;
+            let weak = self.state.hunger().is_weak();
+            let mut found_wood = 0;
+            let mut cut_trees = 0;
+            let map_data = self.state.map_data_mut();
+            for index in 0..TREECOUNT {
+                let Some(tree) = map_data.trees[index] else {
+                    continue;
+                };
+                // saplings are left alone to grow
+                if !tree.position.is_next_to(&(px, py)) || tree.stage == GrowthStage::Sapling {
+                    continue;
+                }
+                let (wood, left) = tree.cut(axe);
+                map_data.trees[index] = left;
+                map_data.mark_changed(tree.position);
+                let fruit = &mut map_data.tree_fruit[index];
+                if left.is_none() {
+                    *fruit = None;
+                } else if fruit.is_some() {
+                    *fruit = Some(FRUIT_REGROW_IN_STEPS);
+                }
+                let wood = if stone_axe && tree.stage != GrowthStage::Stump && wood > 0 { wood + STONE_AXE_BONUS } else { wood };
+                let wood = if weak { wood.saturating_sub(2) } else { wood };
+                cut_trees += 1;
+                // grown trees by the river fall into it and float off as a log, to be fished out further down
+                if tree.stage == GrowthStage::Mature && wood > 0 {
+                    if let Some(water) = map_data.flowing_neighbour(tree.position) {
+                        if map_data.set_adrift(Drifting::new(water, ItemType::WoodPlank, wood)) {
+                            continue;
+                        }
+                    }
+                }
+                found_wood = found_wood.saturating_add(wood);
+            }
+            let planks = self.state.inventory_mut().item_mut(ItemType::WoodPlank);
+            *planks = planks.saturating_add(found_wood);
+            self.state.add_score(cut_trees * TREE_POINTS);
+        }
+
+        if self.button_controller.is_just_pressed(Button::B) {
+            if let Some(target) = self.facing_tile() {
+                self.place(target);
+            }
+        }
+
+        if self.button_controller.is_just_pressed(Button::L) {
+            if let Some(target) = self.facing_tile() {
+                self.deconstruct(target);
+            }
+        }
+    }
+
+    /// Mines the rock in front of the player with a pickaxe, returns false without one or if only rubble is left.
+    fn mine(&mut self, index: usize) -> bool {
+        if *self.state.inventory().item(ItemType::Pickaxe) == 0 {
+            return false;
+        }
+        let weak = self.state.hunger().is_weak();
+        let Some(rock) = self.state.map_data_mut().rocks[index].as_mut() else {
+            return false;
+        };
+        if rock.is_mined() {
+            return false;
+        }
+        let stone = rock.mine();
+        let position = rock.position;
+        self.state.map_data_mut().mark_changed(position);
+        let stone = if weak { stone.saturating_sub(1) } else { stone };
+        let count = self.state.inventory_mut().item_mut(ItemType::Stone);
+        *count = count.saturating_add(stone);
+        self.state.add_score(ROCK_POINTS);
+        return true;
+    }
+
+    /// Breaks up the rock terrain in front of the player with a pickaxe, it gives as much stone as a boulder.
+    fn quarry(&mut self, point: (usize, usize)) -> bool {
+        if *self.state.inventory().item(ItemType::Pickaxe) == 0 || !self.state.quarry(point, self.vram, &mut self.background) {
+            return false;
+        }
+        let stone = RockKind::Boulder.stone();
+        let stone = if self.state.hunger().is_weak() { stone.saturating_sub(1) } else { stone };
+        let count = self.state.inventory_mut().item_mut(ItemType::Stone);
+        *count = count.saturating_add(stone);
+        self.state.add_score(ROCK_POINTS);
+        return true;
+    }
+
+    /// Takes apart the bridge or building in front of the player, giving back some of its planks or stone.
+    fn deconstruct(&mut self, target: (u16, u16)) {
+        if let Some(index) = self.state.map_data().building_at(target) {
+            let Some(building) = self.state.map_data_mut().remove_building(index) else {
+                return;
+            };
+            let (item, cost) = building.kind.cost();
+            let planks = self.state.inventory_mut().item_mut(item);
+            *planks = planks.saturating_add(cost / 2);
+            self.state.upload(self.vram, &mut self.background);
+            return;
+        }
+        let point = (target.0 as usize, target.1 as usize);
+        let durability = self.state.map_data().bridge_durability(point);
+        if durability == 0 {
+            return;
+        }
+        let bridge = if durability == STONE_BRIDGE { Placeable::StoneBridge } else { Placeable::Bridge };
+        let (item, cost) = bridge.cost();
+        self.state.map_data_mut().set_bridge(point, 0);
+        let planks = self.state.inventory_mut().item_mut(item);
+        *planks = planks.saturating_add(salvage(durability, cost));
+        self.state.upload(self.vram, &mut self.background);
+    }
+
+    fn place(&mut self, target: (u16, u16)) {
+        let point = (target.0 as usize, target.1 as usize);
+        if self.state.map_data().has_campfire(target) {
+            if *self.state.inventory().item(ItemType::WoodPlank) > 0 && self.state.map_data_mut().campfire.as_mut().is_some_and(|campfire| campfire.add_plank()) {
+                *self.state.inventory_mut().item_mut(ItemType::WoodPlank) -= 1;
+            }
+            return;
+        }
+        if let Some(building) = self.planned_building() {
+            self.build(building);
+            return;
+        }
+        if self.placing == Placeable::Shovel {
+            if *self.state.inventory().item(ItemType::Shovel) > 0 {
+                self.state.reshape(point, self.vram, &mut self.background);
+            }
+            return;
+        }
+        let terrain = self.state.map_data().get_terrain(point);
+        let is_built_on = self.state.map_data().building_at(target).is_some();
+        let is_open_water = terrain.is_water() && !self.state.map_data().get_bridge_point(point) && self.state.map_data().trap_at(target).is_none() && !is_built_on;
+        let is_free_land = !terrain.is_water() && terrain != Terrain::Rock && self.state.map_data().tree_at(target).is_none() && self.state.map_data().rock_at(target).is_none() && !is_built_on;
+        let (item, count) = self.placing.cost();
+        let valid_terrain = if self.placing.needs_water() { is_open_water } else { is_free_land };
+        if !valid_terrain || *self.state.inventory().item(item) < count || !self.state.map_data().has_room_for(point) {
+            return;
+        }
+        if self.placing == Placeable::Campfire {
+            self.state.map_data_mut().set_campfire(Campfire::new(target, count));
+        } else if let Some(kind) = self.placing.trap_kind() {
+            if !self.state.map_data_mut().place_trap(Trap::new(target, kind)) {
+                return;
+            }
+        } else {
+            let durability = if self.placing == Placeable::StoneBridge { STONE_BRIDGE } else { BRIDGE_DURABILITY };
+            self.state.map_data_mut().set_bridge(point, durability);
+            self.state.add_score(BRIDGE_POINTS);
+            self.state.upload(self.vram, &mut self.background);
+        }
+        *self.state.inventory_mut().item_mut(item) -= count;
+    }
+
+    /// The building that would be put up with B, its footprint starts at the tile in front of the player and grows away
+    /// from them.
+    fn planned_building(&self) -> Option<Building> {
+        let kind = self.placing.building()?;
+        let (tx, ty) = self.facing_tile()?;
+        let (width, height) = kind.footprint();
+        let position = match self.state.player_obj().get_direction() {
+            Direction::UP => (tx.checked_sub((width - 1) / 2)?, ty.checked_sub(height - 1)?),
+            Direction::DOWN => (tx.checked_sub((width - 1) / 2)?, ty),
+            Direction::LEFT => (tx.checked_sub(width - 1)?, ty.checked_sub((height - 1) / 2)?),
+            Direction::RIGHT => (tx, ty.checked_sub((height - 1) / 2)?),
+        };
+        return Some(Building::new(kind, position));
+    }
+
+    fn build(&mut self, building: Building) {
+        let (item, count) = building.kind.cost();
+        if !self.state.can_build(&building) || *self.state.inventory().item(item) < count {
+            return;
+        }
+        if !self.state.map_data_mut().place_building(building) {
+            return;
+        }
+        *self.state.inventory_mut().item_mut(item) -= count;
+        self.state.upload(self.vram, &mut self.background);
+    }
+
+    /// Outlines where the selected building would go, in red if it doesn't fit there.
+    fn step_ghost(&mut self, scroll_cols: i32, scroll_rows: i32) {
+        for obj in self.ghost_obj.iter_mut() {
+            obj.hide();
+        }
+        if self.crafting_cursor.is_some() || self.state.fishing().is_active() {
+            return;
+        }
+        let Some(building) = self.planned_building() else {
+            return;
+        };
+        let frame = if self.state.can_build(&building) { 0 } else { 1 };
+        for ((x, y), obj) in building.tiles().zip(self.ghost_obj.iter_mut()) {
+            let px = (x as i32 - scroll_cols) * 8;
+            let py = (y as i32 - scroll_rows) * 8;
+            obj.set_sprite(self.ghost_sprites[frame].clone());
+            obj.set_position(Vector2D::new(px, py));
+            obj.set_priority(Priority::P1);
+            if Self::on_screen(px, py) {
+                obj.show();
+            }
+        }
+    }
+
+    /// Picks the fruit off every tree next to the player, returns false if none had any.
+    fn pick_fruit(&mut self) -> bool {
+        let (px, py) = self.state.player_obj().get_position();
+        let mut picked = 0u8;
+        for index in 0..TREECOUNT {
+            let Some((tx, ty)) = self.state.map_data().trees[index].map(|tree| tree.position) else {
+                continue;
+            };
+            if (tx, ty).is_next_to(&(px as u16, py as u16)) && self.state.map_data().has_fruit(index) {
+                self.state.map_data_mut().tree_fruit[index] = Some(FRUIT_REGROW_IN_STEPS);
+                self.state.map_data_mut().mark_changed((tx, ty));
+                picked += 1;
+            }
+        }
+        let fruit = self.state.inventory_mut().item_mut(ItemType::Fruit);
+        *fruit = fruit.saturating_add(picked);
+        return picked > 0;
+    }
+
+    /// Cooks a raw fish when standing next to a lit campfire, returns false if there was nothing to cook.
+    fn cook(&mut self) -> bool {
+        let (px, py) = self.state.player_obj().get_position();
+        let Some(campfire) = self.state.map_data().campfire else {
+            return false;
+        };
+        if !campfire.is_lit() || !campfire.position.is_next_to(&(px as u16, py as u16)) || *self.state.inventory().item(ItemType::Fish) == 0 {
+            return false;
+        }
+        *self.state.inventory_mut().item_mut(ItemType::Fish) -= 1;
+        let cooked = self.state.inventory_mut().item_mut(ItemType::CookedFish);
+        *cooked = cooked.saturating_add(1);
+        return true;
+    }
+
+    /// Fishes something floating out of the river.
+    fn collect_drifting(&mut self, index: usize) {
+        let Some(drifting) = self.state.map_data_mut().take_drifting(index) else {
+            return;
+        };
+        let count = self.state.inventory_mut().item_mut(drifting.item);
+        *count = count.saturating_add(drifting.count);
+    }
+
+    /// Takes the fish out of a trap, or picks the trap back up if it's empty.
+    fn collect_trap(&mut self, index: usize) {
+        let Some(trap) = self.state.map_data().traps[index] else {
+            return;
+        };
+        if trap.fish > 0 {
+            let points = self.state.map_data().fish_stock(trap.position).species().points();
+            let fish = self.state.inventory_mut().item_mut(ItemType::Fish);
+            *fish = fish.saturating_add(trap.fish);
+            self.state.add_score(points * trap.fish as u32);
+            if let Some(trap) = self.state.map_data_mut().traps[index].as_mut() {
+                trap.fish = 0;
+            }
+        } else {
+            let item = match trap.kind {
+                TrapKind::FishTrap => ItemType::FishTrap,
+                TrapKind::Net => ItemType::Net,
+            };
+            let count = self.state.inventory_mut().item_mut(item);
+            *count = count.saturating_add(1);
+            self.state.map_data_mut().remove_trap(index);
         }
     }
 
+    fn step_game_over(&mut self) {
+        if self.button_controller.is_just_pressed(Button::START) {
+            // short enough to be typed back in on the title screen, the world stays the same kind
+            let seed = (self.state.rng_mut().gen::<u32>() & SEED_MASK) as u64;
+            let params = self.state.map_data().params();
+            self.state = GameState::new(seed, params);
+            self.state.upload(self.vram, &mut self.background);
+            self.frozen = self.state.season().is_frozen();
+            self.crafting_cursor = None;
+            self.fishing_outcome = None;
+        }
+    }
+
+    fn step_crafting(&mut self, cursor: usize) {
+        if self.button_controller.is_just_pressed(Button::B) || self.button_controller.is_just_pressed(Button::START) {
+            self.crafting_cursor = None;
+        } else if self.button_controller.is_just_pressed(Button::UP) {
+            self.crafting_cursor = Some((cursor + RECIPES.len() - 1) % RECIPES.len());
+        } else if self.button_controller.is_just_pressed(Button::DOWN) {
+            self.crafting_cursor = Some((cursor + 1) % RECIPES.len());
+        } else if self.button_controller.is_just_pressed(Button::A) {
+            RECIPES[cursor].craft(self.state.inventory_mut());
+        }
+    }
+
+    fn step_fishing(&mut self) {
+        let pressed_a = self.button_controller.is_just_pressed(Button::A);
+        let pressed_b = self.button_controller.is_just_pressed(Button::B);
+        let target = self.state.fishing().target();
+        let (fishing, rng) = self.state.fishing_mut();
+        let outcome = if pressed_b {
+            fishing.cancel();
+            None
+        } else if pressed_a {
+            fishing.pull(rng)
+        } else {
+            fishing.tick(rng)
+        };
+        match outcome {
+            Some(FishingOutcome::Caught(species)) => {
+                let fish = self.state.inventory_mut().item_mut(ItemType::Fish);
+                *fish = fish.saturating_add(1);
+                if let Some(target) = target {
+                    self.state.map_data_mut().fish_stock_mut(target).take();
+                }
+                let points = if self.state.time_of_day() == TimeOfDay::Night { species.points() * 2 } else { species.points() };
+                self.state.add_score(points);
+            }
+            _ => {}
+        }
+        if let Some(outcome) = outcome {
+            self.fishing_outcome = Some((outcome, FISHING_OUTCOME_FRAMES));
+        }
+    }
+
+    /// Rolls the weather for the coming hour, redrawing the river if it flooded, drained, froze or thawed.
+    fn change_weather(&mut self) {
+        let was = (self.state.weather(), self.state.map_data().flooded);
+        let season = self.state.season();
+        let weather = Weather::roll(season, self.state.rng_mut());
+        self.state.set_weather(weather);
+        if was != (self.state.weather(), self.state.map_data().flooded) || self.frozen != season.is_frozen() {
+            if self.frozen && !season.is_frozen() {
+                // the river thawed under whoever was out on the ice
+                self.state.wash_ashore();
+            }
+            self.frozen = season.is_frozen();
+            self.state.upload(self.vram, &mut self.background);
+        }
+    }
+
+    /// Rain and snow fall across the screen, each particle starting from its own column and height.
+    fn step_weather_particles(&mut self, current_frame: usize) {
+        let (sprite, speed) = match self.state.weather() {
+            Weather::Clear => {
+                for obj in self.weather_obj.iter_mut() {
+                    obj.hide();
+                }
+                return;
+            }
+            Weather::Rain => (0, 3),
+            Weather::Storm => (0, 5),
+            Weather::Snow => (1, 1),
+        };
+        for (i, obj) in self.weather_obj.iter_mut().enumerate() {
+            let x = (i * 31 + 7) % 240;
+            let y = (i * 53 + current_frame * speed) % 160;
+            // snow drifts sideways while it falls
+            let x = if sprite == 1 { (x + (current_frame / 16 + i) % 8) % 240 } else { x };
+            obj.set_sprite(self.weather_sprites[sprite].clone());
+            obj.set_position(Vector2D::new(x as i32, y as i32));
+            obj.set_priority(Priority::P1);
+            obj.show();
+        }
+    }
+
+    fn apply_tint(&mut self) {
+        let tint = self.state.tint();
+        if tint == self.tint {
+            return;
+        }
+        self.vram.set_background_palettes(&tint.apply_palettes(background::PALETTES));
+        self.sprite_palette.apply(self.tint, tint);
+        self.tint = tint;
+    }
+
+    fn scroll_rows(&self) -> i32 {
+        let y = self.state.player_obj().get_position().1 as i32;
+        return (if y > Y_SCROLL_THRESHOLD { y - Y_SCROLL_THRESHOLD } else { 0 }).min((MAPHEIGHT as i32) - 20);
+    }
+
+    fn scroll_cols(&self) -> i32 {
+        let x = self.state.player_obj().get_position().0 as i32;
+        return (if x > X_SCROLL_THRESHOLD { x - X_SCROLL_THRESHOLD } else { 0 }).min((MAPWIDTH as i32) - 30);
+    }
+
+    /// Whether a sprite at this screen position can be seen, OAM coordinates wrap around so far away sprites must be hidden.
+    fn on_screen(px: i32, py: i32) -> bool {
+        return px > -16 && px < 240 && py > -16 && py < 160;
+    }
+
+    fn facing_tile(&self) -> Option<(u16, u16)> {
+        let (px, py) = self.state.player_obj().get_position();
+        let (px, py) = (px as u16, py as u16);
+        let (tx, ty) = match self.state.player_obj().get_direction() {
+            Direction::UP => (px, py.wrapping_sub(1)),
+            Direction::DOWN => (px, py + 1),
+            Direction::LEFT => (px.wrapping_sub(1), py),
+            Direction::RIGHT => (px + 1, py),
+        };
+        if tx >= MAPWIDTH as u16 || ty >= MAPHEIGHT as u16 {
+            return None;
+        }
+        return Some((tx, ty));
+    }
+
     pub fn update(&mut self) {
-        let y_scroll = (if (self.state.player_obj().get_position().1 as i16) > Y_SCROLL_THRESHOLD as i16 {
-            self.state.player_obj().get_position().1 as i16 - Y_SCROLL_THRESHOLD as i16
-        } else { 0 }).min((MAPHEIGHT as i16) - 20) * 8;
-        self.background.set_scroll_pos((0i16, y_scroll));
+        let x_scroll = self.scroll_cols() as i16 * 8;
+        let y_scroll = ((self.state.map_data().origin_row() + self.scroll_rows() as usize) % BACKGROUND_ROWS) as i16 * 8;
+        self.background.set_scroll_pos((x_scroll, y_scroll));
         self.background.commit(self.vram);
         let mut oam_iter = self.oam.iter();
         oam_iter.next().unwrap().set(&self.player_obj);
-        for (oam, obj) in oam_iter.take(self.tree_obj.len()).zip(self.tree_obj.iter()) {
+        oam_iter.next().unwrap().set(&self.bobber_obj);
+        oam_iter.next().unwrap().set(&self.campfire_obj);
+        for (oam, obj) in oam_iter.by_ref().take(self.ghost_obj.len()).zip(self.ghost_obj.iter()) {
+            oam.set(obj);
+        }
+        for (oam, obj) in oam_iter.by_ref().take(self.tree_obj.len()).zip(self.tree_obj.iter()) {
+            oam.set(obj);
+        }
+        for (oam, obj) in oam_iter.by_ref().take(self.rock_obj.len()).zip(self.rock_obj.iter()) {
+            oam.set(obj);
+        }
+        for (oam, obj) in oam_iter.by_ref().take(self.trap_obj.len()).zip(self.trap_obj.iter()) {
+            oam.set(obj);
+        }
+        for (oam, obj) in oam_iter.by_ref().take(self.drift_obj.len()).zip(self.drift_obj.iter()) {
+            oam.set(obj);
+        }
+        for (oam, obj) in oam_iter.take(self.weather_obj.len()).zip(self.weather_obj.iter()) {
             oam.set(obj);
         }
         self.update_hud();
@@ -185,8 +835,8 @@ where
         self.menumap.set_visible(true);
         self.menumap.clear(self.vram);
 
-        let menu_width = 10u16;
-        let menu_height = 4u16;
+        let menu_width = 20u16;
+        let menu_height = 8u16;
         let menu_base_x = 30 - menu_width;
         let menu_base_y = 20 - menu_height;
 
@@ -202,19 +852,94 @@ where
             }
         }
 
-        self.menumap.set_tile(self.vram, (menu_base_x + 1, menu_base_y + 1), &background::font.tiles, background::font.tile_settings[15]);
-        self.menumap.set_tile(self.vram, (menu_base_x + 1, menu_base_y + 2), &background::font.tiles, background::font.tile_settings[15]);
-        let wood_digits = self.state.inventory().item(ItemType::WoodPlank).digits();
+        self.draw_counter(menu_base_x + 1, menu_base_y + 1, Self::item_icon(ItemType::WoodPlank), *self.state.inventory().item(ItemType::WoodPlank), false);
+        self.draw_counter(menu_base_x + 7, menu_base_y + 1, Self::item_icon(ItemType::Fish), *self.state.inventory().item(ItemType::Fish), false);
+        self.draw_counter(menu_base_x + 13, menu_base_y + 1, HUNGER_ICON_TILE, self.state.hunger().value(), false);
+        let placing_icon = match self.placing {
+            Placeable::Bridge => BRIDGE_ICON_TILE,
+            Placeable::StoneBridge => STONE_BRIDGE_ICON_TILE,
+            Placeable::FishTrap => Self::item_icon(ItemType::FishTrap),
+            Placeable::Net => Self::item_icon(ItemType::Net),
+            Placeable::Campfire => CAMPFIRE_ICON_TILE,
+            Placeable::Building(_) => BUILD_ICON_TILE,
+            Placeable::Shovel => Self::item_icon(ItemType::Shovel),
+        };
+        let (placing_item, placing_count) = self.placing.cost();
+        let placing_tile = background::font.tile_settings[placing_icon + if *self.state.inventory().item(placing_item) < placing_count { GREYED_TILE_OFFSET } else { 0 }];
+        self.menumap.set_tile(self.vram, (menu_base_x + 18, menu_base_y + 1), &background::font.tiles, placing_tile);
+        self.menumap.set_tile(self.vram, (menu_base_x + 18, menu_base_y + 2), &background::font.tiles, placing_tile);
+        self.draw_counter(menu_base_x + 1, menu_base_y + 3, Self::item_icon(ItemType::CookedFish), *self.state.inventory().item(ItemType::CookedFish), false);
+        self.draw_counter(menu_base_x + 7, menu_base_y + 3, Self::item_icon(ItemType::Fruit), *self.state.inventory().item(ItemType::Fruit), false);
+        self.draw_counter(menu_base_x + 13, menu_base_y + 3, Self::item_icon(ItemType::Stone), *self.state.inventory().item(ItemType::Stone), false);
+        if let Some(campfire) = self.state.map_data().campfire {
+            self.draw_counter(menu_base_x + 1, menu_base_y + 5, CAMPFIRE_ICON_TILE, campfire.fuel(), !campfire.is_lit());
+        }
 
-        for (i, digit) in wood_digits.enumerate() {
-            self.menumap.set_tile(self.vram, ((menu_base_x + 2 + i as u16), menu_base_y + 1), &background::font.tiles, background::font.tile_settings[digit as usize]);
-            self.menumap.set_tile(self.vram, ((menu_base_x + 2 + i as u16), menu_base_y + 2), &background::font.tiles, background::font.tile_settings[16 + (digit as usize)]);
+        if let FishingState::Reeling { cursor, zone, pulls, .. } = *self.state.fishing() {
+            let gauge_width = REEL_GAUGE_MAX as u16 + 3;
+            let gauge_height = 4u16;
+            let gauge_base_x = (30 - gauge_width) / 2;
+            let gauge_base_y = 20 - gauge_height - menu_height;
+            for y in 0..gauge_height {
+                for x in 0..gauge_width {
+                    let tile_setting = Self::get_window_tile_setting(x, y, gauge_width, gauge_height);
+                    self.menumap.set_tile(self.vram, (gauge_base_x + x, gauge_base_y + y), &background::font.tiles, tile_setting);
+                }
+            }
+            for i in 0..=REEL_GAUGE_MAX {
+                let tile_id = if i == cursor {
+                    GAUGE_CURSOR_TILE
+                } else if i >= zone.0 && i <= zone.1 {
+                    GAUGE_ZONE_TILE
+                } else {
+                    GAUGE_EMPTY_TILE
+                };
+                self.menumap.set_tile(self.vram, (gauge_base_x + 1 + i as u16, gauge_base_y + 1), &background::font.tiles, background::font.tile_settings[tile_id]);
+            }
+            for i in 0..REEL_PULLS_TO_LAND {
+                let tile_id = if i < pulls { GAUGE_FILL_TILE } else { GAUGE_EMPTY_TILE };
+                self.menumap.set_tile(self.vram, (gauge_base_x + 1 + i as u16, gauge_base_y + 2), &background::font.tiles, background::font.tile_settings[tile_id]);
+            }
+        }
+
+        if let Some((outcome, _)) = self.fishing_outcome {
+            let outcome_width = 3u16;
+            let outcome_height = 4u16;
+            let outcome_base_x = (30 - outcome_width) / 2;
+            let outcome_base_y = 20 - outcome_height - menu_height;
+            for y in 0..outcome_height {
+                for x in 0..outcome_width {
+                    let tile_setting = Self::get_window_tile_setting(x, y, outcome_width, outcome_height);
+                    self.menumap.set_tile(self.vram, (outcome_base_x + x, outcome_base_y + y), &background::font.tiles, tile_setting);
+                }
+            }
+            // the fish when one was caught, greyed out when nothing bit, and a greyed rod when it got away
+            let icon = match outcome {
+                FishingOutcome::Caught(_) => Self::item_icon(ItemType::Fish),
+                FishingOutcome::NoBite => GREYED_TILE_OFFSET + Self::item_icon(ItemType::Fish),
+                FishingOutcome::BaitLost | FishingOutcome::CatchLost => GREYED_TILE_OFFSET + Self::item_icon(ItemType::FishingRod),
+            };
+            self.menumap.set_tile(self.vram, (outcome_base_x + 1, outcome_base_y + 1), &background::font.tiles, background::font.tile_settings[icon]);
+            self.menumap.set_tile(self.vram, (outcome_base_x + 1, outcome_base_y + 2), &background::font.tiles, background::font.tile_settings[icon]);
         }
 
+        let score_width = 9u16;
+        let score_height = 4u16;
+        let score_base_y = 20 - score_height;
+        for y in 0..score_height {
+            for x in 0..score_width {
+                let tile_setting = Self::get_window_tile_setting(x, y, score_width, score_height);
+                self.menumap.set_tile(self.vram, (x, score_base_y + y), &background::font.tiles, tile_setting);
+            }
+        }
+        self.draw_score(1, score_base_y + 1, self.state.score());
+
         let clock_width = 9;
         let clock_height = 4;
 
-        let clock_base_x = if self.state.player_obj().get_position().0 < 16 { 30 - clock_width } else { 2 };
+        // keep the clock on the other half of the screen from the player
+        let player_col = self.state.player_obj().get_position().0 as i32 - self.scroll_cols();
+        let clock_base_x = if player_col < 15 { 30 - clock_width } else { 2 };
         let clock_base_y = 1;
 
         for y in 0..clock_height {
@@ -229,9 +954,7 @@ where
             }
         }
 
-        let time = self.state.frame() / FRAMES_PER_STEP;
-        let ss = time % 60;
-        let mm = (time / 60) % 24;
+        let (mm, ss) = self.state.clock();
 
         for i in 0..2 {
             let digit = (mm / (10_u16.pow(1-i)) as usize) % 10;
@@ -246,11 +969,118 @@ where
         }
 
 
+        if let Some(cursor) = self.crafting_cursor {
+            self.draw_crafting_menu(cursor);
+        }
+
+        if self.state.hunger().is_starved() {
+            let letters = [GAME_OVER_TILES[0], GAME_OVER_TILES[1], GAME_OVER_TILES[2], GAME_OVER_TILES[3], 30, GAME_OVER_TILES[4], GAME_OVER_TILES[5], GAME_OVER_TILES[3], GAME_OVER_TILES[6]];
+            let window_width = letters.len() as u16 + 2;
+            let window_height = 4 + 2 * HIGH_SCORE_COUNT as u16;
+            let window_base_x = (30 - window_width) / 2;
+            let window_base_y = 2u16;
+            for y in 0..window_height {
+                for x in 0..window_width {
+                    let tile_setting = Self::get_window_tile_setting(x, y, window_width, window_height);
+                    self.menumap.set_tile(self.vram, (window_base_x + x, window_base_y + y), &background::font.tiles, tile_setting);
+                }
+            }
+            for (i, tile_id) in letters.into_iter().enumerate() {
+                self.menumap.set_tile(self.vram, (window_base_x + 1 + i as u16, window_base_y + 1), &background::font.tiles, background::font.tile_settings[tile_id]);
+            }
+            let scores = *self.high_scores.scores();
+            for (i, score) in scores.into_iter().enumerate() {
+                self.draw_score(window_base_x + 1, window_base_y + 3 + 2 * i as u16, score);
+            }
+        }
+
         self.menumap.set_scroll_pos((4i16, 4i16));
 
         self.menumap.commit(self.vram);
     }
 
+    /// The crafting menu, with the world's seed underneath the recipes so it can be shared.
+    fn draw_crafting_menu(&mut self, cursor: usize) {
+        let menu_width = 17u16;
+        let menu_height = 4 + 2 * RECIPES.len() as u16;
+        let menu_base_x = 6u16;
+        let menu_base_y = (20 - menu_height) / 2;
+
+        for y in 0..menu_height {
+            for x in 0..menu_width {
+                let tile_setting = Self::get_window_tile_setting(x, y, menu_width, menu_height);
+                self.menumap.set_tile(self.vram, (menu_base_x + x, menu_base_y + y), &background::font.tiles, tile_setting);
+            }
+        }
+
+        for (i, recipe) in RECIPES.iter().enumerate() {
+            let row_y = menu_base_y + 1 + 2 * i as u16;
+            let greyed = !recipe.can_craft(self.state.inventory());
+            if i == cursor {
+                self.menumap.set_tile(self.vram, (menu_base_x + 1, row_y), &background::font.tiles, background::font.tile_settings[MENU_CURSOR_TILE]);
+            }
+            self.draw_counter(menu_base_x + 2, row_y, Self::item_icon(recipe.output), *self.state.inventory().item(recipe.output), greyed);
+            for (j, &(item, count)) in recipe.cost.iter().enumerate() {
+                self.draw_counter(menu_base_x + 7 + 5 * j as u16, row_y, Self::item_icon(item), count, greyed);
+            }
+        }
+        let map_data = self.state.map_data();
+        let shared = WorldPreset::of(&map_data.params()).unwrap_or(WorldPreset::River).share_seed(map_data.seed());
+        self.draw_seed(menu_base_x + 2, menu_base_y + 1 + 2 * RECIPES.len() as u16, shared);
+    }
+
+    fn draw_seed(&mut self, x: u16, y: u16, seed: u32) {
+        self.menumap.set_tile(self.vram, (x, y), &background::font.tiles, background::font.tile_settings[SEED_ICON_TILE]);
+        self.menumap.set_tile(self.vram, (x, y + 1), &background::font.tiles, background::font.tile_settings[SEED_ICON_TILE]);
+
+        for i in 0..SEED_DIGITS {
+            let digit = (seed >> (4 * (SEED_DIGITS - 1 - i))) as usize & 0xf;
+            let tile = if digit < 10 { digit } else { HEX_DIGIT_TILE + digit - 10 };
+            self.menumap.set_tile(self.vram, (x + 1 + i as u16, y), &background::font.tiles, background::font.tile_settings[tile]);
+            self.menumap.set_tile(self.vram, (x + 1 + i as u16, y + 1), &background::font.tiles, background::font.tile_settings[16 + tile]);
+        }
+    }
+
+    fn draw_counter(&mut self, x: u16, y: u16, icon: usize, value: u8, greyed: bool) {
+        let offset = if greyed { GREYED_TILE_OFFSET } else { 0 };
+        self.menumap.set_tile(self.vram, (x, y), &background::font.tiles, background::font.tile_settings[offset + icon]);
+        self.menumap.set_tile(self.vram, (x, y + 1), &background::font.tiles, background::font.tile_settings[offset + icon]);
+
+        for (i, digit) in value.digits().enumerate() {
+            self.menumap.set_tile(self.vram, (x + 1 + i as u16, y), &background::font.tiles, background::font.tile_settings[offset + digit as usize]);
+            self.menumap.set_tile(self.vram, (x + 1 + i as u16, y + 1), &background::font.tiles, background::font.tile_settings[offset + 16 + (digit as usize)]);
+        }
+    }
+
+    fn draw_score(&mut self, x: u16, y: u16, score: u32) {
+        self.menumap.set_tile(self.vram, (x, y), &background::font.tiles, background::font.tile_settings[SCORE_ICON_TILE]);
+        self.menumap.set_tile(self.vram, (x, y + 1), &background::font.tiles, background::font.tile_settings[SCORE_ICON_TILE]);
+
+        // only the last six digits fit in the window
+        for (i, digit) in score.min(999_999).digits().skip(4).enumerate() {
+            self.menumap.set_tile(self.vram, (x + 1 + i as u16, y), &background::font.tiles, background::font.tile_settings[digit as usize]);
+            self.menumap.set_tile(self.vram, (x + 1 + i as u16, y + 1), &background::font.tiles, background::font.tile_settings[16 + (digit as usize)]);
+        }
+    }
+
+    fn item_icon(item: ItemType) -> usize {
+        match item {
+            ItemType::WoodPlank => 15,
+            ItemType::Fish => 31,
+            ItemType::FishingRod => 36,
+            ItemType::Net => 37,
+            ItemType::Axe => 38,
+            ItemType::Raft => 39,
+            ItemType::FishTrap => 41,
+            ItemType::CookedFish => 44,
+            ItemType::Fruit => 45,
+            ItemType::Shovel => 47,
+            ItemType::Pickaxe => 106,
+            ItemType::Stone => 107,
+            ItemType::StoneAxe => 108,
+        }
+    }
+
     fn get_window_tile_setting(x: u16, y: u16, w: u16, h: u16) -> TileSetting {
         let is_left_border = x == 0;
         let is_right_border = x == w - 1;
@@ -273,9 +1103,36 @@ where
     }
 }
 
+pub const X_SCROLL_THRESHOLD: i32 = 15;
 pub const Y_SCROLL_THRESHOLD: i32 = 10;
 pub const FRAME_SCALE: usize = 5;
-pub const TREECOUNT: usize = 4;
-pub const MAPHEIGHT: usize = 32;
+pub use rsim_world::{MAPHEIGHT, MAPWIDTH, ROCKCOUNT, TREECOUNT};
+pub const BACKGROUND_ROWS: usize = 64;
+pub const GAUGE_EMPTY_TILE: usize = 32;
+pub const GAUGE_ZONE_TILE: usize = 33;
+pub const GAUGE_CURSOR_TILE: usize = 34;
+pub const GAUGE_FILL_TILE: usize = 35;
+pub const MENU_CURSOR_TILE: usize = 40;
+pub const GREYED_TILE_OFFSET: usize = 48;
+pub const BRIDGE_ICON_TILE: usize = 42;
+pub const CAMPFIRE_ICON_TILE: usize = 43;
+pub const BUILD_ICON_TILE: usize = 46;
+pub const STONE_BRIDGE_ICON_TILE: usize = 109;
+pub const HUNGER_ICON_TILE: usize = 96;
+pub const SCORE_ICON_TILE: usize = 104;
+pub const SEED_ICON_TILE: usize = 105;
+/// A to F, their bottom halves are a row further down like the digits'.
+pub const HEX_DIGIT_TILE: usize = 112;
+/// Hex digits of a seed that can be typed in on the title screen.
+pub const SEED_DIGITS: usize = 8;
+/// G, A, M, E, O, V, R
+pub const GAME_OVER_TILES: [usize; 7] = [97, 98, 99, 100, 101, 102, 103];
 pub static MAN_SPRITE: &Graphics = agb::include_aseprite!("man.aseprite");
-pub static TREE_SPRITE: &Graphics = agb::include_aseprite!("tree.aseprite");
\ No newline at end of file
+pub static TREE_SPRITE: &Graphics = agb::include_aseprite!("tree.aseprite");
+pub static BOBBER_SPRITE: &Graphics = agb::include_aseprite!("bobber.aseprite");
+pub static TRAP_SPRITE: &Graphics = agb::include_aseprite!("trap.aseprite");
+pub static CAMPFIRE_SPRITE: &Graphics = agb::include_aseprite!("campfire.aseprite");
+pub static WEATHER_SPRITE: &Graphics = agb::include_aseprite!("weather.aseprite");
+pub static GHOST_SPRITE: &Graphics = agb::include_aseprite!("ghost.aseprite");
+pub static DRIFT_SPRITE: &Graphics = agb::include_aseprite!("drift.aseprite");
+pub static ROCK_SPRITE: &Graphics = agb::include_aseprite!("rock.aseprite");
\ No newline at end of file
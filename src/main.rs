@@ -1,4 +1,3 @@
-#![feature(variant_count)]
 #![no_std]
 #![no_main]
 
@@ -16,7 +15,10 @@ use agb::{Gba};
 use agb::display::font::TextRenderer;
 use agbrs_flash::FlashMemory;
 use once_cell::sync::Lazy;
-use gamemode::GameMode;
+use gamemode::{GameMode, SEED_DIGITS};
+use state::highscore::HighScores;
+use state::serialized::SerializedState;
+use state::worldgen::{WorldGenParams, WorldPreset};
 mod gamemode;
 mod state;
 mod traits;
@@ -38,6 +40,59 @@ struct Vector3D<T: Number> {
     pub z: T,
 }
 
+/// Entries of the title menu, `Continue` is only there when there's a save to continue. `World` picks the kind of
+/// world a new game is generated as.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum MenuEntry {
+    NewGame,
+    CustomSeed,
+    World,
+    Continue,
+}
+
+impl MenuEntry {
+    fn next(self, can_reload: bool) -> Self {
+        match self {
+            MenuEntry::NewGame => MenuEntry::CustomSeed,
+            MenuEntry::CustomSeed => MenuEntry::World,
+            MenuEntry::World if can_reload => MenuEntry::Continue,
+            MenuEntry::World | MenuEntry::Continue => MenuEntry::NewGame,
+        }
+    }
+
+    fn previous(self, can_reload: bool) -> Self {
+        match self {
+            MenuEntry::NewGame if can_reload => MenuEntry::Continue,
+            MenuEntry::NewGame | MenuEntry::Continue => MenuEntry::World,
+            MenuEntry::World => MenuEntry::CustomSeed,
+            MenuEntry::CustomSeed => MenuEntry::NewGame,
+        }
+    }
+}
+
+/// A seed being typed in one hex digit at a time, LEFT and RIGHT pick the digit and UP and DOWN change it.
+#[derive(Debug, Copy, Clone, Default)]
+struct SeedEntry {
+    seed: u32,
+    cursor: usize,
+}
+
+impl SeedEntry {
+    fn digit(&self, index: usize) -> u32 {
+        return (self.seed >> (4 * (SEED_DIGITS - 1 - index))) & 0xf;
+    }
+
+    fn change_digit(&mut self, up: bool) {
+        let shift = 4 * (SEED_DIGITS - 1 - self.cursor);
+        let digit = (self.digit(self.cursor) + if up { 1 } else { 15 }) % 16;
+        self.seed = (self.seed & !(0xf << shift)) | digit << shift;
+    }
+
+    fn move_cursor(&mut self, right: bool) {
+        self.cursor = (self.cursor + if right { 1 } else { SEED_DIGITS - 1 }) % SEED_DIGITS;
+    }
+}
+
 const C_128: Lazy<FixedNum<8>> = Lazy::new(|| FixedNum::new(128));
 const C_120: Lazy<FixedNum<8>> = Lazy::new(|| FixedNum::from_raw(120 << 8));
 
@@ -57,21 +112,38 @@ fn m7_hbl_a(cam: Vector3D<FixedNum<8>>, vcount: u16) -> Mode7Params {
     Mode7Params { pa, pc, x, y }
 }
 
-fn draw_menu_items(fg_color: u8, bg_color: u8, font_fg: &mut RegularMap, vram: &mut VRamManager, newgame: bool, can_reload: bool, renderer: &mut TextRenderer) -> () {
+fn draw_menu_items(fg_color: u8, bg_color: u8, font_fg: &mut RegularMap, vram: &mut VRamManager, selected: MenuEntry, seed_entry: Option<SeedEntry>, preset: WorldPreset, can_reload: bool, best_score: u32, renderer: &mut TextRenderer) -> () {
     renderer.clear(vram);
     font_fg.clear(vram);
     let mut writer = renderer.writer(fg_color, bg_color, font_fg, vram);
-
-    writeln!(&mut writer, "{}New Game", if newgame {">"} else {" "}).unwrap();
+    let marker = |entry: MenuEntry| if entry == selected { ">" } else { " " };
+
+    writeln!(&mut writer, "{}New Game", marker(MenuEntry::NewGame)).unwrap();
+    writeln!(&mut writer, "{}Custom Seed", marker(MenuEntry::CustomSeed)).unwrap();
+    if let Some(seed_entry) = seed_entry {
+        write!(&mut writer, " ").unwrap();
+        for i in 0..SEED_DIGITS {
+            if i == seed_entry.cursor {
+                write!(&mut writer, "[{:X}]", seed_entry.digit(i)).unwrap();
+            } else {
+                write!(&mut writer, "{:X}", seed_entry.digit(i)).unwrap();
+            }
+        }
+        writeln!(&mut writer).unwrap();
+    }
+    writeln!(&mut writer, "{}{}", marker(MenuEntry::World), preset.name()).unwrap();
     if can_reload {
-        writeln!(&mut writer, "{}Continue", if newgame { " " } else { ">" }).unwrap();
+        writeln!(&mut writer, "{}Continue", marker(MenuEntry::Continue)).unwrap();
+    }
+    if best_score > 0 {
+        writeln!(&mut writer, " Best: {}", best_score).unwrap();
     }
 
     writer.commit();
     font_fg.commit(vram);
 }
 
-fn menu_mode(gba: &mut Gba, memory: &mut FlashMemory) -> (bool, u64) {
+fn menu_mode(gba: &mut Gba, memory: &mut FlashMemory) -> (bool, u64, WorldGenParams) {
     let (tiled, mut vram) = gba.display.video.tiled1();
     let vblank = agb::interrupt::VBlank::get();
     let tileset = &gamemode::background::tiles256.tiles;
@@ -142,47 +214,74 @@ fn menu_mode(gba: &mut Gba, memory: &mut FlashMemory) -> (bool, u64) {
 
 
     let mut input = ButtonController::new();
-    let mut newgame = true;
-    let can_reload = memory.have_structure();
+    let mut selected = MenuEntry::NewGame;
+    let mut seed_entry: Option<SeedEntry> = None;
+    let mut preset = WorldPreset::River;
+    let can_reload = SerializedState::can_load(memory);
+    let best_score = HighScores::load(&mut gba.save).scores()[0];
     let mut renderer = FONT.render_text((10u16, 3u16));
-    draw_menu_items(fg_color, bg_color, &mut font_fg, &mut vram, newgame, can_reload, &mut renderer);
+    draw_menu_items(fg_color, bg_color, &mut font_fg, &mut vram, selected, seed_entry, preset, can_reload, best_score, &mut renderer);
     let mut frame = 0u64;
-    loop {
+    let custom_seed = loop {
         input.update();
-        if (input.is_just_pressed(Button::DOWN) || input.is_just_pressed(Button::UP)) && can_reload {
-            newgame = !newgame;
-            draw_menu_items(fg_color, bg_color, &mut font_fg, &mut vram, newgame, can_reload, &mut renderer);
+        let mut changed = true;
+        if let Some(entry) = seed_entry.as_mut() {
+            if input.is_just_pressed(Button::A) {
+                break Some(entry.seed);
+            } else if input.is_just_pressed(Button::B) {
+                seed_entry = None;
+            } else if input.is_just_pressed(Button::UP) || input.is_just_pressed(Button::DOWN) {
+                entry.change_digit(input.is_just_pressed(Button::UP));
+                preset = WorldPreset::from_shared_seed(entry.seed).0;
+            } else if input.is_just_pressed(Button::LEFT) || input.is_just_pressed(Button::RIGHT) {
+                entry.move_cursor(input.is_just_pressed(Button::RIGHT));
+            } else {
+                changed = false;
+            }
+        } else if selected == MenuEntry::World && (input.is_just_pressed(Button::A) || input.is_just_pressed(Button::RIGHT)) {
+            preset = preset.next();
+        } else if input.is_just_pressed(Button::A) {
+            if selected != MenuEntry::CustomSeed {
+                break None;
+            }
+            seed_entry = Some(SeedEntry { seed: preset.share_seed(0), cursor: 0 });
+        } else if input.is_just_pressed(Button::DOWN) {
+            selected = selected.next(can_reload);
+        } else if input.is_just_pressed(Button::UP) {
+            selected = selected.previous(can_reload);
+        } else {
+            changed = false;
         }
-        if input.is_just_pressed(Button::A) {
-            break;
+        if changed {
+            draw_menu_items(fg_color, bg_color, &mut font_fg, &mut vram, selected, seed_entry, preset, can_reload, best_score, &mut renderer);
         }
         vblank.wait_for_vblank();
         font_fg.set_visible(true);
         floor.set_visible(true);
         bg.set_visible(true);
         frame += 1;
-    }
+    };
     font_fg.set_visible(false);
     floor.set_visible(false);
     bg.set_visible(false);
     drop(ih);
     renderer.clear(&mut vram);
-    return (newgame, frame);
+    // without a seed typed in, the time spent on the title screen stands in for one
+    let (preset, seed) = WorldPreset::from_shared_seed(custom_seed.unwrap_or(preset.share_seed((frame as u32).rotate_left(8) as u64)));
+    return (selected != MenuEntry::Continue, seed, preset.params());
 }
 
 #[agb::entry]
 fn main(mut gba: agb::Gba) -> ! {
     let mut memory = FlashMemory::new_flash_128k(&mut gba);
 
-    let (do_newgame, frame) = menu_mode(&mut gba, &mut memory);
-
-    let seed = frame << 8 | (frame >> (64-8) & 0xff);
+    let (do_newgame, seed, params) = menu_mode(&mut gba, &mut memory);
 
     let (mut tiled, mut vram) = gba.display.video.tiled0();
     let (mut oam, mut spriteloader) = gba.display.object.get_unmanaged();
 
 
-    let mut game = GameMode::new(&mut tiled, &mut vram, &mut oam, &mut spriteloader, memory, !do_newgame, seed);
+    let mut game = GameMode::new(&mut tiled, &mut vram, &mut oam, &mut spriteloader, memory, &mut gba.save, !do_newgame, seed, params);
 
     loop {
         game.step();
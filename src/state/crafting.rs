@@ -0,0 +1,35 @@
+use crate::state::inventory::{Inventory, ItemType};
+
+pub struct Recipe {
+    pub output: ItemType,
+    pub cost: &'static [(ItemType, u8)],
+}
+
+pub const RECIPES: [Recipe; 8] = [
+    Recipe { output: ItemType::FishingRod, cost: &[(ItemType::WoodPlank, 2)] },
+    Recipe { output: ItemType::Axe, cost: &[(ItemType::WoodPlank, 3)] },
+    Recipe { output: ItemType::FishTrap, cost: &[(ItemType::WoodPlank, 4)] },
+    Recipe { output: ItemType::Net, cost: &[(ItemType::WoodPlank, 3), (ItemType::FishingRod, 1)] },
+    Recipe { output: ItemType::Raft, cost: &[(ItemType::WoodPlank, 8), (ItemType::Net, 1)] },
+    Recipe { output: ItemType::Shovel, cost: &[(ItemType::WoodPlank, 3)] },
+    Recipe { output: ItemType::Pickaxe, cost: &[(ItemType::WoodPlank, 4)] },
+    Recipe { output: ItemType::StoneAxe, cost: &[(ItemType::WoodPlank, 2), (ItemType::Stone, 3)] },
+];
+
+impl Recipe {
+    pub fn can_craft(&self, inventory: &Inventory) -> bool {
+        let has_ingredients = self.cost.iter().all(|&(item, count)| *inventory.item(item) >= count);
+        return has_ingredients && *inventory.item(self.output) < u8::MAX;
+    }
+
+    pub fn craft(&self, inventory: &mut Inventory) -> bool {
+        if !self.can_craft(inventory) {
+            return false;
+        }
+        for &(item, count) in self.cost {
+            *inventory.item_mut(item) -= count;
+        }
+        *inventory.item_mut(self.output) += 1;
+        return true;
+    }
+}
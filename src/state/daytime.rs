@@ -0,0 +1,108 @@
+use alloc::vec::Vec;
+use agb::display::palette16::Palette16;
+pub use rsim_world::daytime::TimeOfDay;
+
+pub const fn rgb5(r: u16, g: u16, b: u16) -> u16 {
+    r | (g << 5) | (b << 10)
+}
+
+const DAWN_TINT: u16 = rgb5(26, 18, 24);
+const DUSK_TINT: u16 = rgb5(30, 14, 6);
+const NIGHT_TINT: u16 = rgb5(2, 4, 14);
+/// How far colours get blended towards the tint, out of 16.
+const MAX_TINT_AMOUNT: u8 = 9;
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub struct Tint {
+    colour: u16,
+    amount: u8,
+}
+
+impl Tint {
+    pub const NONE: Tint = Tint { colour: 0, amount: 0 };
+
+    pub const fn new(colour: u16, amount: u8) -> Self {
+        Self { colour, amount }
+    }
+
+    /// Tint for a given time, fading in and out of night during dawn and dusk.
+    pub fn for_time(hour: usize, minute: usize) -> Self {
+        // transitions last two hours, split in eight quarter-hour steps
+        let progress = |start_hour: usize| (((hour - start_hour) * 60 + minute) / 15) as u8;
+        match TimeOfDay::from_hour(hour) {
+            TimeOfDay::Day => Tint::NONE,
+            TimeOfDay::Night => Tint { colour: NIGHT_TINT, amount: MAX_TINT_AMOUNT },
+            TimeOfDay::Dawn => Tint { colour: DAWN_TINT, amount: MAX_TINT_AMOUNT.saturating_sub(progress(5) + 1) },
+            TimeOfDay::Dusk => Tint { colour: DUSK_TINT, amount: (progress(18) + 1).min(MAX_TINT_AMOUNT) },
+        }
+    }
+
+    /// Approximates applying `self` and then `other` as a single tint.
+    pub fn blend(&self, other: &Tint) -> Tint {
+        let total = self.amount as u16 + other.amount as u16;
+        if total == 0 {
+            return Tint::NONE;
+        }
+        let mix = |shift: u16| {
+            let a = (self.colour >> shift) & 0x1f;
+            let b = (other.colour >> shift) & 0x1f;
+            ((a * self.amount as u16 + b * other.amount as u16) / total) << shift
+        };
+        let amount = (total - self.amount as u16 * other.amount as u16 / 16).min(16) as u8;
+        return Tint { colour: mix(0) | mix(5) | mix(10), amount };
+    }
+
+    pub fn apply(&self, colour: u16) -> u16 {
+        let amount = self.amount as i32;
+        let blend = |shift: u16| {
+            let from = ((colour >> shift) & 0x1f) as i32;
+            let to = ((self.colour >> shift) & 0x1f) as i32;
+            ((from + (to - from) * amount / 16) as u16) << shift
+        };
+        return (colour & 0x8000) | blend(0) | blend(5) | blend(10);
+    }
+
+    pub fn apply_palettes(&self, palettes: &[Palette16]) -> Vec<Palette16> {
+        return palettes.iter().map(|palette| {
+            let mut colours = [0u16; 16];
+            for (i, colour) in colours.iter_mut().enumerate() {
+                *colour = self.apply(palette.colour(i));
+            }
+            Palette16::new(colours)
+        }).collect();
+    }
+}
+
+/// Object palette RAM, the sprite palettes come right after the background ones.
+const OBJ_PALETTE: *mut u16 = 0x0500_0200 as *mut u16;
+
+/// Tints the sprites along with the backgrounds. agb loads a sprite's palette when the sprite is first put into VRAM
+/// and can't recolour it afterwards, so this is the one place that reads and writes object palette RAM directly.
+pub struct SpritePalette {
+    untinted: [u16; 256],
+}
+
+impl Default for SpritePalette {
+    fn default() -> Self {
+        return Self { untinted: [0; 256] };
+    }
+}
+
+impl SpritePalette {
+    fn read() -> [u16; 256] {
+        // SAFETY: object palette RAM is always mapped and 256 halfwords long
+        return unsafe { OBJ_PALETTE.cast::<[u16; 256]>().read_volatile() };
+    }
+
+    /// Tints the sprites' colours going from tint `from` to `to`. Their untinted colours are read whenever `from` is
+    /// no tint at all, so sprites loaded while untinted are always picked up.
+    pub fn apply(&mut self, from: Tint, to: Tint) {
+        if from == Tint::NONE {
+            self.untinted = Self::read();
+        }
+        for (i, colour) in self.untinted.iter().enumerate() {
+            // SAFETY: `i` stays within the 256 entries of object palette RAM
+            unsafe { OBJ_PALETTE.add(i).write_volatile(to.apply(*colour)) };
+        }
+    }
+}
@@ -0,0 +1,150 @@
+use rand::Rng;
+use rand_xoshiro::SplitMix64;
+use crate::state::daytime::TimeOfDay;
+use crate::state::fishstock::{FishSpecies, FishStock};
+use crate::state::terrain::Terrain;
+use crate::state::weather::Season;
+
+const BITE_MIN_FRAMES: u16 = 90;
+const BITE_MAX_FRAMES: u16 = 300;
+const BITE_WINDOW_FRAMES: u16 = 40;
+const REEL_TIMEOUT_FRAMES: u16 = 600;
+pub const REEL_GAUGE_MAX: u8 = 11;
+pub const REEL_PULLS_TO_LAND: u8 = 4;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum FishingOutcome {
+    Caught(FishSpecies),
+    NoBite,
+    BaitLost,
+    CatchLost,
+}
+
+#[derive(Copy, Clone, Debug, Default)]
+pub enum FishingState {
+    #[default]
+    Idle,
+    Waiting { target: (u16, u16), frames_left: u16, bite: Option<FishSpecies> },
+    Bite { target: (u16, u16), frames_left: u16, species: FishSpecies },
+    Reeling { target: (u16, u16), frames_left: u16, cursor: u8, rising: bool, zone: (u8, u8), pulls: u8, species: FishSpecies },
+}
+
+impl FishingState {
+    pub fn is_active(&self) -> bool {
+        return !matches!(self, FishingState::Idle);
+    }
+
+    pub fn target(&self) -> Option<(u16, u16)> {
+        match *self {
+            FishingState::Idle => None,
+            FishingState::Waiting { target, .. } | FishingState::Bite { target, .. } | FishingState::Reeling { target, .. } => Some(target),
+        }
+    }
+
+    /// Whether anything bites is decided on cast from the stock of that stretch of river.
+    /// Fish are more active at night, so they bite sooner, and the season and how deep the water is scale the odds of a bite.
+    pub fn cast(&mut self, target: (u16, u16), stock: &FishStock, time_of_day: TimeOfDay, season: Season, terrain: Terrain, rng: &mut SplitMix64) {
+        let mut frames_left = rng.gen_range(BITE_MIN_FRAMES..BITE_MAX_FRAMES);
+        if time_of_day == TimeOfDay::Night {
+            frames_left /= 2;
+        }
+        let chance = stock.bite_chance(time_of_day) as u16 * season.bite_modifier() as u16 / 100 * terrain.bite_modifier() as u16 / 100;
+        let bite = if rng.gen_range(0..100) < chance { Some(stock.species()) } else { None };
+        *self = FishingState::Waiting { target, frames_left, bite };
+    }
+
+    /// Advances the minigame by one frame, returning an outcome once the attempt is over.
+    pub fn tick(&mut self, rng: &mut SplitMix64) -> Option<FishingOutcome> {
+        match self {
+            FishingState::Idle => None,
+            FishingState::Waiting { target, frames_left, bite } => {
+                if *frames_left == 0 {
+                    let Some(species) = *bite else {
+                        *self = FishingState::Idle;
+                        return Some(FishingOutcome::NoBite);
+                    };
+                    *self = FishingState::Bite { target: *target, frames_left: BITE_WINDOW_FRAMES, species };
+                } else {
+                    *frames_left -= 1;
+                }
+                None
+            }
+            FishingState::Bite { frames_left, .. } => {
+                if *frames_left == 0 {
+                    *self = FishingState::Idle;
+                    return Some(FishingOutcome::BaitLost);
+                }
+                *frames_left -= 1;
+                None
+            }
+            FishingState::Reeling { frames_left, cursor, rising, .. } => {
+                if *frames_left == 0 {
+                    *self = FishingState::Idle;
+                    return Some(FishingOutcome::CatchLost);
+                }
+                *frames_left -= 1;
+                // the cursor sweeps back and forth across the gauge, and the fish tugs it along at random
+                if *frames_left % 3 == 0 {
+                    if rng.gen::<u8>() % 16 == 0 {
+                        *rising = !*rising;
+                    }
+                    if *rising && *cursor >= REEL_GAUGE_MAX {
+                        *rising = false;
+                    } else if !*rising && *cursor == 0 {
+                        *rising = true;
+                    } else if *rising {
+                        *cursor += 1;
+                    } else {
+                        *cursor -= 1;
+                    }
+                }
+                None
+            }
+        }
+    }
+
+    /// Handles the reel button. Pulling too early scares the fish away with the bait, pulling
+    /// outside of the gauge's sweet spot snaps the line.
+    pub fn pull(&mut self, rng: &mut SplitMix64) -> Option<FishingOutcome> {
+        match *self {
+            FishingState::Idle => None,
+            FishingState::Waiting { .. } => {
+                *self = FishingState::Idle;
+                Some(FishingOutcome::BaitLost)
+            }
+            FishingState::Bite { target, species, .. } => {
+                *self = FishingState::Reeling {
+                    target,
+                    frames_left: REEL_TIMEOUT_FRAMES,
+                    cursor: 0,
+                    rising: true,
+                    zone: Self::random_zone(rng),
+                    pulls: 0,
+                    species,
+                };
+                None
+            }
+            FishingState::Reeling { target, frames_left, cursor, rising, zone, pulls, species } => {
+                if cursor < zone.0 || cursor > zone.1 {
+                    *self = FishingState::Idle;
+                    return Some(FishingOutcome::CatchLost);
+                }
+                if pulls + 1 >= REEL_PULLS_TO_LAND {
+                    *self = FishingState::Idle;
+                    return Some(FishingOutcome::Caught(species));
+                }
+                *self = FishingState::Reeling { target, frames_left, cursor, rising, zone: Self::random_zone(rng), pulls: pulls + 1, species };
+                None
+            }
+        }
+    }
+
+    pub fn cancel(&mut self) {
+        *self = FishingState::Idle;
+    }
+
+    fn random_zone(rng: &mut SplitMix64) -> (u8, u8) {
+        let start = rng.gen_range(2..REEL_GAUGE_MAX - 2);
+        return (start, start + 2);
+    }
+}
@@ -1,13 +1,38 @@
 use agb::display::tiled::{MapLoan, RegularMap, TiledMap, VRamManager};
-use crate::state::inventory::Inventory;
-use crate::state::map::MapData;
+use alloc::boxed::Box;
+use core::ops::Range;
+use rand::{Rng, SeedableRng};
+use rand_xoshiro::SplitMix64;
+use crate::gamemode::{BACKGROUND_ROWS, FRAMES_PER_STEP, MAPHEIGHT, MAPWIDTH};
+use crate::state::bridge::WALK_WEAR_CHANCE;
+use crate::state::building::Building;
+use crate::state::chunk::CHUNK_HEIGHT;
+use crate::state::daytime::{TimeOfDay, Tint};
+use crate::state::fishing::FishingState;
+use crate::state::grid::Grid;
+use crate::state::hunger::Hunger;
+use crate::state::inventory::{Inventory, ItemType};
+use crate::state::map::{MapData, Tileset};
 use crate::state::player::PlayerObj;
 use crate::state::serialized::SerializedState;
+use crate::state::terrain::Terrain;
+use crate::state::weather::{Season, Weather};
+use crate::state::worldgen::WorldGenParams;
+
+/// How far the player can be washed ashore when the bridge under them breaks, far enough to reach any loaded tile.
+const ASHORE_RANGE: i16 = MAPWIDTH as i16;
+/// Frames it takes to paddle a raft a tile across deep water.
+const RAFT_MOVE_COOLDOWN: u8 = 12;
 
 pub struct GameState {
-    map_data: MapData,
+    map_data: Box<MapData>,
     player_obj: PlayerObj,
     inventory: Inventory,
+    hunger: Hunger,
+    score: u32,
+    weather: Weather,
+    fishing: FishingState,
+    rng: SplitMix64,
     frame: usize,
 }
 
@@ -16,33 +41,216 @@ impl<'obj> GameState {
         let mut player_obj = PlayerObj::new();
         player_obj.set_position((state.player.0, state.player.1));
         player_obj.set_direction(state.player.2);
-        let state = Self {
-            map_data: state.map_data,
+        let mut game_state = Self {
+            map_data: MapData::from_save(state.world),
             inventory: state.inventory,
+            hunger: state.hunger,
+            score: state.score,
+            weather: state.weather,
             player_obj,
+            fishing: FishingState::default(),
+            rng: SplitMix64::seed_from_u64(state.frame as u64),
             frame: state.frame,
         };
-        state
+        // floods aren't saved, they're spread again from the weather
+        game_state.set_weather(state.weather);
+        game_state
     }
 
-    pub fn new(seed_mix: u64) -> Self {
+    pub fn new(seed_mix: u64, params: WorldGenParams) -> Self {
+        let (map_data, spawn) = MapData::gen(seed_mix, params);
+        let mut player_obj = PlayerObj::new();
+        player_obj.set_position((spawn.position.0 as u8, spawn.position.1 as u8));
         let state = Self {
-            map_data: MapData::gen(seed_mix),
-            player_obj: PlayerObj::new(),
+            map_data,
+            player_obj,
             inventory: Inventory::default(),
+            hunger: Hunger::default(),
+            score: 0,
+            weather: Weather::default(),
+            fishing: FishingState::default(),
+            rng: SplitMix64::seed_from_u64(seed_mix),
             frame: 0,
         };
         state
     }
 
     pub fn upload<'a>(&'a mut self, vram: &mut VRamManager, background: &mut MapLoan<RegularMap>) {
-        vram.set_background_palettes(crate::gamemode::background::PALETTES);
-        self.map_data.copy_map_to_bg(vram, background);
+        vram.set_background_palettes(&self.tint().apply_palettes(crate::gamemode::background::PALETTES));
+        self.draw_rows(vram, background, 0..MAPHEIGHT);
         background.set_scroll_pos((0i16, 0i16));
         background.set_visible(true);
         background.commit(vram);
     }
 
+    /// Loads the next chunk once the player walks into the last loaded one, or the previous one when walking
+    /// back into the first, drawing the rows that changed. Returns whether the map moved.
+    pub fn stream(&mut self, vram: &mut VRamManager, background: &mut MapLoan<RegularMap>) -> bool {
+        let (x, y) = self.player_obj.get_position();
+        let forward = if y as usize >= 2 * CHUNK_HEIGHT {
+            true
+        } else if (y as usize) < CHUNK_HEIGHT && self.map_data.origin_row() > 0 {
+            false
+        } else {
+            return false;
+        };
+        let rows = self.map_data.shift(forward);
+        let y = if forward { y - CHUNK_HEIGHT as u8 } else { y + CHUNK_HEIGHT as u8 };
+        self.player_obj.set_position((x, y));
+        self.set_weather(self.weather);
+        self.draw_rows(vram, background, rows);
+        return true;
+    }
+
+    /// Frames it takes to walk onto a point, `None` if something's in the way.
+    pub fn walk_cooldown(&self, point: (usize, usize)) -> Option<u8> {
+        let tile = (point.0 as u16, point.1 as u16);
+        if self.map_data.has_tree(tile) || self.map_data.has_rock(tile) || self.map_data.has_campfire(tile) {
+            return None;
+        }
+        if let Some(building) = self.map_data.building_at(tile).and_then(|index| self.map_data.buildings[index]) {
+            return if building.kind.is_walkable() { Some(0) } else { None };
+        }
+        let terrain = self.map_data.get_terrain(point);
+        // bridges and ice are as easy to walk on as grass
+        if self.map_data.get_bridge_point(point) || (terrain.is_water() && self.season().is_frozen()) {
+            return Some(0);
+        }
+        if terrain == Terrain::DeepWater && *self.inventory.item(ItemType::Raft) > 0 {
+            return Some(RAFT_MOVE_COOLDOWN);
+        }
+        return terrain.move_cooldown();
+    }
+
+    /// Whether a building fits where it is, without building it over the player.
+    pub fn can_build(&self, building: &Building) -> bool {
+        let (x, y) = self.player_obj.get_position();
+        return self.map_data.can_build(building, (x as u16, y as u16));
+    }
+
+    /// Wears down the bridge the player just stepped onto now and then.
+    pub fn walk_on(&mut self, point: (usize, usize), vram: &mut VRamManager, background: &mut MapLoan<RegularMap>) {
+        if !self.map_data.get_bridge_point(point) || self.rng.gen::<u8>() % WALK_WEAR_CHANCE != 0 {
+            return;
+        }
+        self.map_data.wear_bridge(point);
+        self.redraw_rows(point.1..point.1 + 1, vram, background);
+        self.wash_ashore();
+    }
+
+    /// Lets the weather wear the bridges down, called once every step.
+    pub fn weather_bridges(&mut self, vram: &mut VRamManager, background: &mut MapLoan<RegularMap>) {
+        let Some(chance) = self.weather.bridge_wear_chance() else {
+            return;
+        };
+        if let Some(rows) = self.map_data.wear_bridges(chance, &mut self.rng) {
+            self.redraw_rows(rows, vram, background);
+            self.wash_ashore();
+        }
+    }
+
+    /// Digs out or fills in a tile with the shovel, letting the river spill into or drain from the new shape.
+    pub fn reshape(&mut self, point: (usize, usize), vram: &mut VRamManager, background: &mut MapLoan<RegularMap>) -> bool {
+        if !self.map_data.reshape(point) {
+            return false;
+        }
+        self.set_weather(self.weather);
+        let reach = self.weather.flood_level() as usize;
+        self.redraw_rows(point.1.saturating_sub(reach)..point.1 + reach + 1, vram, background);
+        return true;
+    }
+
+    /// Breaks up the rock terrain at a point, redrawing it and the flood around it.
+    pub fn quarry(&mut self, point: (usize, usize), vram: &mut VRamManager, background: &mut MapLoan<RegularMap>) -> bool {
+        if !self.map_data.quarry(point) {
+            return false;
+        }
+        self.set_weather(self.weather);
+        let reach = self.weather.flood_level() as usize;
+        self.redraw_rows(point.1.saturating_sub(reach)..point.1 + reach + 1, vram, background);
+        return true;
+    }
+
+    /// Lets the current carry everything floating downstream, the player too when they're out on a raft and not fishing.
+    /// Without a raft they're washed ashore instead. Called once every step.
+    pub fn drift(&mut self) {
+        if self.season().is_frozen() {
+            return;
+        }
+        self.map_data.drift();
+        // deep water is no place to be without a raft, however the player got there
+        self.wash_ashore();
+        if !self.is_rafting() || self.fishing.is_active() {
+            return;
+        }
+        let (x, y) = self.player_obj.get_position();
+        if let Some((x, y)) = self.map_data.drift_target((x as usize, y as usize)).filter(|&next| self.walk_cooldown(next).is_some()) {
+            self.player_obj.set_position((x as u8, y as u8));
+        }
+    }
+
+    /// Whether the player is out on deep water with a raft under them.
+    fn is_rafting(&self) -> bool {
+        if *self.inventory.item(ItemType::Raft) == 0 {
+            return false;
+        }
+        let (x, y) = self.player_obj.get_position();
+        let point = (x as usize, y as usize);
+        let on_structure = self.map_data.get_bridge_point(point) || self.map_data.building_at((x as u16, y as u16)).is_some();
+        return self.map_data.get_terrain(point) == Terrain::DeepWater && !on_structure;
+    }
+
+    /// Draws rows that changed, along with the rows next to them as their tiles depend on their neighbours.
+    fn redraw_rows(&mut self, rows: Range<usize>, vram: &mut VRamManager, background: &mut MapLoan<RegularMap>) {
+        let rows = rows.start.saturating_sub(1)..(rows.end + 1).min(MAPHEIGHT);
+        self.draw_rows(vram, background, rows);
+    }
+
+    /// Draws some of the map's rows, the background wraps around so every world row has a fixed place in it.
+    fn draw_rows(&self, mut vram: &mut VRamManager, background: &mut MapLoan<RegularMap>, rows: Range<usize>) {
+        let frozen = self.season().is_frozen();
+        for y in rows {
+            let bg_y = ((self.map_data.origin_row() + y) % BACKGROUND_ROWS) as u16;
+            for x in 0..MAPWIDTH {
+                let (tileset, tileid) = self.map_data.tile_at((x, y), frozen);
+                let tiles = match tileset {
+                    Tileset::Map => &crate::gamemode::background::tiles16,
+                    Tileset::Shore => &crate::gamemode::background::shore,
+                    Tileset::Buildings => &crate::gamemode::background::buildings,
+                };
+                background.set_tile(
+                    &mut vram, (x as u16, bg_y),
+                    &tiles.tiles,
+                    tiles.tile_settings[tileid],
+                );
+            }
+        }
+    }
+
+    /// Moves the player to the closest tile they can stand on if the bridge or ice under them is gone, dropping their line.
+    pub fn wash_ashore(&mut self) {
+        let (x, y) = self.player_obj.get_position();
+        if self.walk_cooldown((x as usize, y as usize)).is_some() {
+            return;
+        }
+        self.fishing = FishingState::default();
+        for range in 1..=ASHORE_RANGE {
+            for dy in -range..=range {
+                for dx in -range..=range {
+                    if dx.abs() != range && dy.abs() != range {
+                        continue;
+                    }
+                    // tiles off the left or top wrap around and end up out of bounds
+                    let point = ((x as i16 + dx) as usize, (y as i16 + dy) as usize);
+                    if Grid::<Terrain>::in_bounds(point) && self.walk_cooldown(point).is_some() {
+                        self.player_obj.set_position((point.0 as u8, point.1 as u8));
+                        return;
+                    }
+                }
+            }
+        }
+    }
+
     pub fn map_data(&self) -> &MapData {
         return &self.map_data;
     }
@@ -64,10 +272,96 @@ impl<'obj> GameState {
     pub fn inventory_mut(&mut self) -> &mut Inventory {
         return &mut self.inventory;
     }
+
+    pub fn hunger(&self) -> &Hunger {
+        return &self.hunger;
+    }
+
+    pub fn hunger_mut(&mut self) -> &mut Hunger {
+        return &mut self.hunger;
+    }
+
+    pub fn eat(&mut self) -> bool {
+        return self.hunger.eat(&mut self.inventory);
+    }
+
+    pub fn score(&self) -> u32 {
+        return self.score;
+    }
+
+    pub fn add_score(&mut self, points: u32) {
+        self.score = self.score.saturating_add(points);
+    }
+
+    pub fn weather(&self) -> Weather {
+        return self.weather;
+    }
+
+    /// Changes the weather, flooding or draining the river banks to match.
+    pub fn set_weather(&mut self, weather: Weather) {
+        self.weather = weather;
+        let (x, y) = self.player_obj.get_position();
+        self.map_data.set_flood_level(weather.flood_level(), (x as u16, y as u16));
+    }
+
+    /// Spreads the forests, keeping the player's tile free of saplings.
+    pub fn spread_trees(&mut self) {
+        let (x, y) = self.player_obj.get_position();
+        self.map_data.spread_trees((x as u16, y as u16), &mut self.rng);
+    }
+
+    /// Grows the trees, holding back the one the player is standing in until they step out of it.
+    pub fn tick_trees(&mut self) {
+        let (x, y) = self.player_obj.get_position();
+        self.map_data.tick_trees((x as u16, y as u16));
+    }
+
+    /// Lets mined out rocks come back, as long as the player isn't standing on their rubble.
+    pub fn tick_rocks(&mut self) {
+        let (x, y) = self.player_obj.get_position();
+        self.map_data.tick_rocks((x as u16, y as u16));
+    }
+
+    pub fn fishing(&self) -> &FishingState {
+        return &self.fishing;
+    }
+
+    pub fn fishing_mut(&mut self) -> (&mut FishingState, &mut SplitMix64) {
+        return (&mut self.fishing, &mut self.rng);
+    }
+
+    pub fn rng_mut(&mut self) -> &mut SplitMix64 {
+        return &mut self.rng;
+    }
+
     pub fn frame(&self) -> usize {
         return self.frame;
     }
 
+    /// In-game (hour, minute), every step is a minute.
+    pub fn clock(&self) -> (usize, usize) {
+        let time = self.frame / FRAMES_PER_STEP;
+        return ((time / 60) % 24, time % 60);
+    }
+
+    /// Days since the game started, counting from 0.
+    pub fn day(&self) -> usize {
+        return self.frame / FRAMES_PER_STEP / (24 * 60);
+    }
+
+    pub fn season(&self) -> Season {
+        return Season::from_day(self.day());
+    }
+
+    pub fn time_of_day(&self) -> TimeOfDay {
+        return TimeOfDay::from_hour(self.clock().0);
+    }
+
+    pub fn tint(&self) -> Tint {
+        let (hour, minute) = self.clock();
+        return Tint::for_time(hour, minute).blend(&self.weather.tint());
+    }
+
     pub fn step_frame(&mut self) -> usize {
         self.frame += 1;
         return self.frame;
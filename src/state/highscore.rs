@@ -0,0 +1,67 @@
+use agb::save::SaveManager;
+
+pub const HIGH_SCORE_COUNT: usize = 5;
+/// The table lives in the last sector of the 128k flash. agbrs_flash writes the game save from the start of the
+/// same flash, everything before this offset is left to it.
+pub const HIGH_SCORE_OFFSET: usize = 0x1F000;
+const HIGH_SCORE_MAGIC: u32 = 0x52534853;
+
+#[derive(Default, Copy, Clone)]
+pub struct HighScores {
+    scores: [u32; HIGH_SCORE_COUNT],
+}
+
+impl HighScores {
+    pub fn load(save: &mut SaveManager) -> Self {
+        let mut buffer = [0u8; 4 * (HIGH_SCORE_COUNT + 1)];
+        let Ok(mut access) = save.access() else {
+            return Self::default();
+        };
+        if access.read(HIGH_SCORE_OFFSET, &mut buffer).is_err() {
+            return Self::default();
+        }
+        let word = |i: usize| u32::from_le_bytes([buffer[i * 4], buffer[i * 4 + 1], buffer[i * 4 + 2], buffer[i * 4 + 3]]);
+        if word(0) != HIGH_SCORE_MAGIC {
+            return Self::default();
+        }
+        let mut table = Self::default();
+        for (i, score) in table.scores.iter_mut().enumerate() {
+            *score = word(i + 1);
+        }
+        return table;
+    }
+
+    pub fn store(&self, save: &mut SaveManager) {
+        let mut buffer = [0u8; 4 * (HIGH_SCORE_COUNT + 1)];
+        buffer[0..4].copy_from_slice(&HIGH_SCORE_MAGIC.to_le_bytes());
+        for (i, score) in self.scores.iter().enumerate() {
+            buffer[(i + 1) * 4..(i + 2) * 4].copy_from_slice(&score.to_le_bytes());
+        }
+        let Ok(mut access) = save.access() else {
+            agb::println!("Could not access flash to store high scores");
+            return;
+        };
+        let range = HIGH_SCORE_OFFSET..HIGH_SCORE_OFFSET + buffer.len();
+        let Ok(mut block) = access.prepare_write(range) else {
+            agb::println!("Could not erase flash to store high scores");
+            return;
+        };
+        if block.write(HIGH_SCORE_OFFSET, &buffer).is_err() {
+            agb::println!("Could not write high scores");
+        }
+    }
+
+    pub fn scores(&self) -> &[u32; HIGH_SCORE_COUNT] {
+        return &self.scores;
+    }
+
+    /// Inserts a score keeping the table sorted, returns its rank if it made it in.
+    pub fn insert(&mut self, score: u32) -> Option<usize> {
+        let rank = self.scores.iter().position(|&s| score > s)?;
+        for i in (rank + 1..HIGH_SCORE_COUNT).rev() {
+            self.scores[i] = self.scores[i - 1];
+        }
+        self.scores[rank] = score;
+        return Some(rank);
+    }
+}
@@ -0,0 +1,60 @@
+use serde::{Deserialize, Serialize};
+use crate::state::inventory::{Inventory, ItemType};
+
+pub const HUNGER_MAX: u8 = 99;
+pub const HUNGER_WEAK_THRESHOLD: u8 = 30;
+const STEPS_PER_HUNGER_POINT: u8 = 4;
+
+/// Foods in the order they get eaten, paired with how much hunger they restore.
+const FOODS: [(ItemType, u8); 3] = [(ItemType::CookedFish, 40), (ItemType::Fruit, 20), (ItemType::Fish, 15)];
+
+#[derive(Copy, Clone, Serialize, Deserialize)]
+pub struct Hunger {
+    value: u8,
+    steps: u8,
+}
+
+impl Default for Hunger {
+    fn default() -> Self {
+        Self { value: HUNGER_MAX, steps: 0 }
+    }
+}
+
+impl Hunger {
+    pub fn value(&self) -> u8 {
+        return self.value;
+    }
+
+    pub fn is_weak(&self) -> bool {
+        return self.value < HUNGER_WEAK_THRESHOLD;
+    }
+
+    pub fn is_starved(&self) -> bool {
+        return self.value == 0;
+    }
+
+    /// Called once every step, drains a point of hunger every `STEPS_PER_HUNGER_POINT` steps.
+    pub fn tick(&mut self) {
+        self.steps += 1;
+        if self.steps >= STEPS_PER_HUNGER_POINT {
+            self.steps = 0;
+            self.value = self.value.saturating_sub(1);
+        }
+    }
+
+    /// Eats the first food available in the inventory, returns false if there was nothing to eat.
+    pub fn eat(&mut self, inventory: &mut Inventory) -> bool {
+        if self.value >= HUNGER_MAX {
+            return false;
+        }
+        for (food, value) in FOODS {
+            let count = inventory.item_mut(food);
+            if *count > 0 {
+                *count -= 1;
+                self.value = self.value.saturating_add(value).min(HUNGER_MAX);
+                return true;
+            }
+        }
+        return false;
+    }
+}
@@ -1,5 +1,11 @@
-pub mod map;
 pub mod player;
 pub mod serialized;
-pub mod inventory;
 pub mod gamestate;
+pub mod fishing;
+pub mod crafting;
+pub mod hunger;
+pub mod highscore;
+pub mod daytime;
+pub mod placeable;
+pub mod weather;
+pub use rsim_world::{autotile, bridge, building, campfire, chunk, drift, earthwork, fishstock, grid, inventory, map, rock, spawn, terrain, trap, tree, worldgen};
@@ -0,0 +1,70 @@
+use crate::state::building::BuildingKind;
+use crate::state::inventory::ItemType;
+use crate::state::trap::TrapKind;
+
+/// What gets placed in front of the player when pressing B, cycled with R.
+#[derive(Copy, Clone, PartialEq, Eq)]
+pub enum Placeable {
+    Bridge,
+    /// A bridge that never wears down.
+    StoneBridge,
+    FishTrap,
+    Net,
+    Campfire,
+    Building(BuildingKind),
+    /// Digs out the land in front of the player or fills in the shallow water there.
+    Shovel,
+}
+
+impl Placeable {
+    const ALL: [Placeable; 11] = [
+        Placeable::Bridge,
+        Placeable::StoneBridge,
+        Placeable::FishTrap,
+        Placeable::Net,
+        Placeable::Campfire,
+        Placeable::Building(BuildingKind::Hut),
+        Placeable::Building(BuildingKind::Dock),
+        Placeable::Building(BuildingKind::Fence),
+        Placeable::Building(BuildingKind::Shed),
+        Placeable::Building(BuildingKind::DryingRack),
+        Placeable::Shovel,
+    ];
+
+    pub fn next(&self) -> Self {
+        let index = Self::ALL.iter().position(|p| p == self).unwrap();
+        return Self::ALL[(index + 1) % Self::ALL.len()];
+    }
+
+    /// Items it takes, the shovel is only needed and never used up.
+    pub fn cost(&self) -> (ItemType, u8) {
+        match self {
+            Placeable::Bridge => (ItemType::WoodPlank, 2),
+            Placeable::StoneBridge => (ItemType::Stone, 2),
+            Placeable::FishTrap => (ItemType::FishTrap, 1),
+            Placeable::Net => (ItemType::Net, 1),
+            Placeable::Campfire => (ItemType::WoodPlank, 2),
+            Placeable::Building(kind) => kind.cost(),
+            Placeable::Shovel => (ItemType::Shovel, 1),
+        }
+    }
+
+    pub fn needs_water(&self) -> bool {
+        return !matches!(self, Placeable::Campfire | Placeable::Building(_) | Placeable::Shovel);
+    }
+
+    pub fn trap_kind(&self) -> Option<TrapKind> {
+        match self {
+            Placeable::Bridge | Placeable::StoneBridge | Placeable::Campfire | Placeable::Building(_) | Placeable::Shovel => None,
+            Placeable::FishTrap => Some(TrapKind::FishTrap),
+            Placeable::Net => Some(TrapKind::Net),
+        }
+    }
+
+    pub fn building(&self) -> Option<BuildingKind> {
+        match self {
+            Placeable::Building(kind) => Some(*kind),
+            Placeable::Bridge | Placeable::StoneBridge | Placeable::FishTrap | Placeable::Net | Placeable::Campfire | Placeable::Shovel => None,
+        }
+    }
+}
@@ -1,4 +1,5 @@
 use serde::{Deserialize, Serialize};
+use crate::gamemode::{MAPHEIGHT, MAPWIDTH};
 
 #[derive(Clone, Copy, Serialize, Deserialize)]
 pub enum Direction {
@@ -30,9 +31,9 @@ impl PlayerObj {
         let (x, y) = self.position;
         match direction {
             Direction::UP => (x, y.saturating_sub(1)),
-            Direction::DOWN => (x, (y + 1).clamp(0, 31)),
+            Direction::DOWN => (x, (y + 1).clamp(0, MAPHEIGHT as u8 - 1)),
             Direction::LEFT => (x.saturating_sub(1), y),
-            Direction::RIGHT => ((x + 1).clamp(0, 31), y),
+            Direction::RIGHT => ((x + 1).clamp(0, MAPWIDTH as u8 - 1), y),
         }
     }
 
@@ -1,12 +1,202 @@
+use core::fmt;
+use agbrs_flash::FlashMemory;
+use serde::ser::{self, Serializer};
 use serde::{Deserialize, Serialize};
+use crate::state::hunger::Hunger;
 use crate::state::inventory::Inventory;
-use crate::state::map::MapData;
+use crate::state::chunk::WorldSave;
+use crate::state::highscore::HIGH_SCORE_OFFSET;
 use crate::state::player::Direction;
+use crate::state::weather::Weather;
 
+/// Written first in every save, "RSV" followed by the version of its layout. Bumped whenever the layout changes, saves
+/// of any other version are left alone and a new game is started instead.
+pub const SAVE_VERSION: u32 = 0x52535601;
+
+/// Everything saved to flash. It's written with a borrowed `WorldSaveRef` for `W` and read back with a `WorldSave`.
 #[derive(Serialize, Deserialize)]
-pub struct SerializedState {
-    pub map_data: MapData,
+pub struct SerializedState<W = WorldSave> {
+    pub version: u32,
+    pub world: W,
     pub inventory: Inventory,
+    pub hunger: Hunger,
+    pub score: u32,
+    pub weather: Weather,
     pub player: (u8, u8, Direction),
     pub frame: usize,
-}
\ No newline at end of file
+}
+
+/// Just the start of a save, to check its version before decoding the rest.
+#[derive(Deserialize)]
+struct SaveVersion {
+    version: u32,
+}
+
+impl SerializedState {
+    /// Whether there's a save of the current version to go back to.
+    pub fn can_load(memory: &mut FlashMemory) -> bool {
+        return memory.have_structure() && memory.read_structure::<SaveVersion>().is_some_and(|save| save.version == SAVE_VERSION);
+    }
+
+    /// Reads the save back, `None` if there's none or it was written with another layout.
+    pub fn load(memory: &mut FlashMemory) -> Option<Self> {
+        if !Self::can_load(memory) {
+            return None;
+        }
+        return memory.read_structure::<Self>().filter(|save| save.version == SAVE_VERSION);
+    }
+}
+
+impl<W: Serialize> SerializedState<W> {
+    /// Writes the save, as long as it stays clear of the high score table further up the flash.
+    pub fn store(&self, memory: &mut FlashMemory) {
+        let mut size = EncodedSize(0);
+        if self.serialize(&mut size).is_err() || size.0 > HIGH_SCORE_OFFSET {
+            agb::println!("Save is too big to store");
+            return;
+        }
+        memory.write_structure(self);
+    }
+}
+
+/// Bytes a length or an enum's variant can take up, as a varint of the widest integer they're written as.
+const LENGTH_BYTES: usize = 10;
+const VARIANT_BYTES: usize = 5;
+
+/// Counts the bytes a value can take up once encoded. Every field is counted at the most any compact encoding gives it,
+/// varints take a byte more for every seven bits of the integer.
+struct EncodedSize(usize);
+
+macro_rules! count_bytes {
+    ($($method:ident($ty:ty) => $bytes:expr),* $(,)?) => {
+        $(fn $method(self, _: $ty) -> Result<(), fmt::Error> {
+            self.0 += $bytes;
+            return Ok(());
+        })*
+    };
+}
+
+impl Serializer for &mut EncodedSize {
+    type Ok = ();
+    type Error = fmt::Error;
+    type SerializeSeq = Self;
+    type SerializeTuple = Self;
+    type SerializeTupleStruct = Self;
+    type SerializeTupleVariant = Self;
+    type SerializeMap = Self;
+    type SerializeStruct = Self;
+    type SerializeStructVariant = Self;
+
+    count_bytes! {
+        serialize_bool(bool) => 1,
+        serialize_i8(i8) => 1,
+        serialize_u8(u8) => 1,
+        serialize_i16(i16) => 3,
+        serialize_u16(u16) => 3,
+        serialize_i32(i32) => 5,
+        serialize_u32(u32) => 5,
+        serialize_i64(i64) => 10,
+        serialize_u64(u64) => 10,
+        serialize_f32(f32) => 4,
+        serialize_f64(f64) => 8,
+        serialize_char(char) => 4,
+        serialize_unit_struct(&'static str) => 0,
+    }
+
+    fn serialize_str(self, value: &str) -> Result<(), fmt::Error> {
+        return self.serialize_bytes(value.as_bytes());
+    }
+
+    fn serialize_bytes(self, value: &[u8]) -> Result<(), fmt::Error> {
+        self.0 += LENGTH_BYTES + value.len();
+        return Ok(());
+    }
+
+    fn serialize_none(self) -> Result<(), fmt::Error> {
+        self.0 += 1;
+        return Ok(());
+    }
+
+    fn serialize_some<T: ?Sized + Serialize>(self, value: &T) -> Result<(), fmt::Error> {
+        self.0 += 1;
+        return value.serialize(self);
+    }
+
+    fn serialize_unit(self) -> Result<(), fmt::Error> {
+        return Ok(());
+    }
+
+    fn serialize_unit_variant(self, _: &'static str, _: u32, _: &'static str) -> Result<(), fmt::Error> {
+        self.0 += VARIANT_BYTES;
+        return Ok(());
+    }
+
+    fn serialize_newtype_struct<T: ?Sized + Serialize>(self, _: &'static str, value: &T) -> Result<(), fmt::Error> {
+        return value.serialize(self);
+    }
+
+    fn serialize_newtype_variant<T: ?Sized + Serialize>(self, _: &'static str, _: u32, _: &'static str, value: &T) -> Result<(), fmt::Error> {
+        self.0 += VARIANT_BYTES;
+        return value.serialize(self);
+    }
+
+    fn serialize_seq(self, _: Option<usize>) -> Result<Self, fmt::Error> {
+        self.0 += LENGTH_BYTES;
+        return Ok(self);
+    }
+
+    fn serialize_tuple(self, _: usize) -> Result<Self, fmt::Error> {
+        return Ok(self);
+    }
+
+    fn serialize_tuple_struct(self, _: &'static str, _: usize) -> Result<Self, fmt::Error> {
+        return Ok(self);
+    }
+
+    fn serialize_tuple_variant(self, _: &'static str, _: u32, _: &'static str, _: usize) -> Result<Self, fmt::Error> {
+        self.0 += VARIANT_BYTES;
+        return Ok(self);
+    }
+
+    fn serialize_map(self, _: Option<usize>) -> Result<Self, fmt::Error> {
+        self.0 += LENGTH_BYTES;
+        return Ok(self);
+    }
+
+    fn serialize_struct(self, _: &'static str, _: usize) -> Result<Self, fmt::Error> {
+        return Ok(self);
+    }
+
+    fn serialize_struct_variant(self, _: &'static str, _: u32, _: &'static str, _: usize) -> Result<Self, fmt::Error> {
+        self.0 += VARIANT_BYTES;
+        return Ok(self);
+    }
+}
+
+/// Fields and elements of compound values are counted one after the other, with nothing in between.
+macro_rules! count_fields {
+    ($($trait:ident { $($method:ident($($name:ty),*)),* }),* $(,)?) => {
+        $(impl ser::$trait for &mut EncodedSize {
+            type Ok = ();
+            type Error = fmt::Error;
+
+            $(fn $method<T: ?Sized + Serialize>(&mut self, $(_: $name,)* value: &T) -> Result<(), fmt::Error> {
+                return value.serialize(&mut **self);
+            })*
+
+            fn end(self) -> Result<(), fmt::Error> {
+                return Ok(());
+            }
+        })*
+    };
+}
+
+count_fields! {
+    SerializeSeq { serialize_element() },
+    SerializeTuple { serialize_element() },
+    SerializeTupleStruct { serialize_field() },
+    SerializeTupleVariant { serialize_field() },
+    SerializeMap { serialize_key(), serialize_value() },
+    SerializeStruct { serialize_field(&'static str) },
+    SerializeStructVariant { serialize_field(&'static str) },
+}
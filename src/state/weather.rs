@@ -0,0 +1,92 @@
+use rand::Rng;
+use rand_xoshiro::SplitMix64;
+use serde::{Deserialize, Serialize};
+use crate::state::daytime::{rgb5, Tint};
+
+/// In-game days every season lasts.
+const DAYS_PER_SEASON: usize = 3;
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum Season {
+    Spring,
+    Summer,
+    Autumn,
+    Winter,
+}
+
+impl Season {
+    pub fn from_day(day: usize) -> Self {
+        match (day / DAYS_PER_SEASON) % 4 {
+            0 => Season::Spring,
+            1 => Season::Summer,
+            2 => Season::Autumn,
+            _ => Season::Winter,
+        }
+    }
+
+    /// Percentage applied to bite chances, fish barely feed under the ice.
+    pub fn bite_modifier(&self) -> u8 {
+        match self {
+            Season::Spring => 100,
+            Season::Summer => 80,
+            Season::Autumn => 110,
+            Season::Winter => 40,
+        }
+    }
+
+    pub fn is_frozen(&self) -> bool {
+        return *self == Season::Winter;
+    }
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Default, Serialize, Deserialize)]
+pub enum Weather {
+    #[default]
+    Clear,
+    Rain,
+    Storm,
+    Snow,
+}
+
+impl Weather {
+    /// Rolls the weather for the next hour.
+    pub fn roll(season: Season, rng: &mut SplitMix64) -> Self {
+        let roll = rng.gen::<u8>() % 100;
+        match season {
+            Season::Winter => if roll < 50 { Weather::Snow } else { Weather::Clear },
+            Season::Summer => if roll < 10 { Weather::Storm } else { Weather::Clear },
+            Season::Spring | Season::Autumn => match roll {
+                0..=29 => Weather::Rain,
+                30..=39 => Weather::Storm,
+                _ => Weather::Clear,
+            },
+        }
+    }
+
+    pub fn tint(&self) -> Tint {
+        match self {
+            Weather::Clear => Tint::NONE,
+            Weather::Rain => Tint::new(rgb5(10, 12, 16), 4),
+            Weather::Storm => Tint::new(rgb5(6, 7, 10), 7),
+            Weather::Snow => Tint::new(rgb5(28, 30, 31), 4),
+        }
+    }
+
+    /// How many tiles the river spills over its banks.
+    pub fn flood_level(&self) -> u8 {
+        match self {
+            Weather::Rain => 1,
+            Weather::Storm => 2,
+            Weather::Clear | Weather::Snow => 0,
+        }
+    }
+
+    /// One in this many steps wears down every bridge out in it, `None` if it doesn't.
+    pub fn bridge_wear_chance(&self) -> Option<u16> {
+        match self {
+            Weather::Rain => Some(300),
+            Weather::Storm => Some(60),
+            Weather::Clear | Weather::Snow => None,
+        }
+    }
+}
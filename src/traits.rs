@@ -15,6 +15,19 @@ impl Digits for u8 {
     }
 }
 
+impl Digits for u32 {
+    fn digits(&self) -> impl Iterator<Item=u8> {
+        let mut num = *self;
+        const DIGIT_COUNT: usize = (u32::MAX.ilog10() + 1) as usize;
+        let mut digits: [u8; DIGIT_COUNT] = [0; DIGIT_COUNT];
+        for i in 0..DIGIT_COUNT {
+            digits[DIGIT_COUNT - i - 1] = (num % 10) as u8;
+            num /= 10;
+        }
+        return digits.into_iter();
+    }
+}
+
 pub trait NextTo {
     fn is_next_to(&self, other: &Self) -> bool;
 }
@@ -0,0 +1,98 @@
+use crate::bridge::{BRIDGE_DURABILITY, STONE_BRIDGE};
+use crate::terrain::Terrain;
+
+/// Bits of a neighbourhood mask, the four sides come first and the corners follow clockwise from the north-east.
+pub const NORTH: u8 = 1;
+pub const EAST: u8 = 2;
+pub const SOUTH: u8 = 4;
+pub const WEST: u8 = 8;
+/// Offsets of the neighbours in the same order as the mask's bits.
+pub const NEIGHBOURS: [(isize, isize); 8] = [(0, -1), (1, 0), (0, 1), (-1, 0), (1, -1), (1, 1), (-1, 1), (-1, -1)];
+
+/// Sides next to each corner, in the corners' bit order.
+const CORNERS: [u8; 4] = [NORTH | EAST, SOUTH | EAST, SOUTH | WEST, NORTH | WEST];
+/// Every combination of land on the sides and in the corners that can be told apart.
+const SHORE_TILES: usize = 47;
+const SHALLOW_SHORE: usize = 0;
+const DEEP_SHORE: usize = SHALLOW_SHORE + SHORE_TILES;
+const ICE_SHORE: usize = DEEP_SHORE + SHORE_TILES;
+const BRIDGE_HORIZONTAL: usize = ICE_SHORE + SHORE_TILES;
+const BRIDGE_VERTICAL: usize = BRIDGE_HORIZONTAL + 1;
+/// Bridge ends on land, one per side the bridge leaves from.
+const BRIDGE_CAP: usize = BRIDGE_VERTICAL + 1;
+/// Worn and then badly damaged bridges, each horizontal then vertical like the intact ones, followed by stone bridges.
+const BRIDGE_WORN: usize = BRIDGE_CAP + 4;
+const BRIDGE_DAMAGED: usize = BRIDGE_WORN + 2;
+const BRIDGE_STONE: usize = BRIDGE_DAMAGED + 2;
+
+/// Where the tiles for each combination of land on the four sides start in `shore.aseprite`. A corner only matters when
+/// both sides next to it are water, so every combination is followed by one tile per combination of its open corners.
+const SIDE_OFFSETS: [usize; 16] = side_offsets();
+
+const fn open_corners(sides: u8) -> u32 {
+    let mut count = 0;
+    let mut i = 0;
+    while i < CORNERS.len() {
+        if sides & CORNERS[i] == 0 {
+            count += 1;
+        }
+        i += 1;
+    }
+    return count;
+}
+
+const fn side_offsets() -> [usize; 16] {
+    let mut offsets = [0; 16];
+    let mut total = 0;
+    let mut sides = 0;
+    while sides < 16 {
+        offsets[sides] = total;
+        total += 1 << open_corners(sides as u8);
+        sides += 1;
+    }
+    return offsets;
+}
+
+const _: () = assert!(SIDE_OFFSETS[15] + 1 == SHORE_TILES);
+
+/// Tile in `shore.aseprite` for a water tile, given the mask of its neighbours that are land.
+pub fn shore_tile(terrain: Terrain, frozen: bool, land: u8) -> usize {
+    let sides = land & 0xf;
+    let mut corners = 0;
+    let mut bit = 0;
+    for (i, corner) in CORNERS.iter().enumerate() {
+        if sides & corner == 0 {
+            if land & (0x10 << i) != 0 {
+                corners |= 1 << bit;
+            }
+            bit += 1;
+        }
+    }
+    let base = if frozen {
+        ICE_SHORE
+    } else if terrain == Terrain::DeepWater {
+        DEEP_SHORE
+    } else {
+        SHALLOW_SHORE
+    };
+    return base + SIDE_OFFSETS[sides as usize] + corners;
+}
+
+/// Tile for a bridge, running along the other bridges next to it or else between the land it connects, and looking
+/// more broken the less durability it has left. Stone bridges don't wear.
+pub fn bridge_tile(bridges: u8, land: u8, durability: u8) -> usize {
+    let is_vertical = |mask: u8| mask & (NORTH | SOUTH) != 0 && mask & (EAST | WEST) == 0;
+    let vertical = is_vertical(bridges) || (bridges == 0 && is_vertical(land));
+    let base = match BRIDGE_DURABILITY.saturating_sub(durability) {
+        _ if durability == STONE_BRIDGE => BRIDGE_STONE,
+        0 => BRIDGE_HORIZONTAL,
+        1 => BRIDGE_WORN,
+        _ => BRIDGE_DAMAGED,
+    };
+    return base + vertical as usize;
+}
+
+/// End cap drawn on land where a bridge starts, `None` if there's no bridge next to it.
+pub fn bridge_cap_tile(bridges: u8) -> Option<usize> {
+    return [NORTH, EAST, SOUTH, WEST].iter().position(|side| bridges & side != 0).map(|side| BRIDGE_CAP + side);
+}
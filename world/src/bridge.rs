@@ -0,0 +1,15 @@
+/// Wear a newly built bridge can take before it breaks and drops back into the river.
+pub const BRIDGE_DURABILITY: u8 = 3;
+/// Kept in place of the durability for a bridge built out of stone, which never wears down.
+pub const STONE_BRIDGE: u8 = BRIDGE_DURABILITY + 1;
+/// Bits the durability of every bridge tile takes up in a saved chunk.
+pub const DURABILITY_BITS: usize = 3;
+/// One in this many steps onto a bridge wears it down.
+pub const WALK_WEAR_CHANCE: u8 = 8;
+
+const _: () = assert!(STONE_BRIDGE < 1 << DURABILITY_BITS);
+
+/// Planks or stone given back by taking apart a bridge that cost `cost` to build, a worn bridge isn't worth much anymore.
+pub fn salvage(durability: u8, cost: u8) -> u8 {
+    return cost * durability.min(BRIDGE_DURABILITY) / (BRIDGE_DURABILITY * 2);
+}
@@ -0,0 +1,101 @@
+use serde::{Deserialize, Serialize};
+use crate::inventory::ItemType;
+use crate::terrain::Terrain;
+
+/// Buildings the player can have up at once, including the ones in chunks that aren't loaded.
+pub const MAX_BUILDINGS: usize = 16;
+/// Tiles taken up by the largest footprint, the preview has a sprite for each of them.
+pub const MAX_FOOTPRINT: usize = 6;
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub enum BuildingKind {
+    Hut,
+    Dock,
+    Fence,
+    Shed,
+    DryingRack,
+}
+
+impl BuildingKind {
+    /// Width and height in tiles.
+    pub fn footprint(&self) -> (u16, u16) {
+        match self {
+            BuildingKind::Hut => (2, 2),
+            BuildingKind::Dock => (3, 1),
+            BuildingKind::Fence => (1, 1),
+            BuildingKind::Shed => (3, 2),
+            BuildingKind::DryingRack => (2, 1),
+        }
+    }
+
+    pub fn cost(&self) -> (ItemType, u8) {
+        match self {
+            BuildingKind::Hut => (ItemType::WoodPlank, 8),
+            BuildingKind::Dock => (ItemType::WoodPlank, 4),
+            BuildingKind::Fence => (ItemType::WoodPlank, 1),
+            BuildingKind::Shed => (ItemType::WoodPlank, 10),
+            BuildingKind::DryingRack => (ItemType::WoodPlank, 3),
+        }
+    }
+
+    /// Whether one of its tiles can stand on this terrain, docks are built out over the water.
+    pub fn allows(&self, terrain: Terrain) -> bool {
+        match self {
+            BuildingKind::Dock => terrain.is_water(),
+            BuildingKind::Hut | BuildingKind::Fence | BuildingKind::Shed | BuildingKind::DryingRack => !terrain.is_water() && terrain != Terrain::Rock,
+        }
+    }
+
+    /// Whether it has to be built against the shore.
+    pub fn needs_shore(&self) -> bool {
+        return *self == BuildingKind::Dock;
+    }
+
+    /// Whether it can be walked over like a bridge rather than around.
+    pub fn is_walkable(&self) -> bool {
+        return *self == BuildingKind::Dock;
+    }
+
+    /// Index of its top left tile in `buildings.aseprite`, the rest follow row by row.
+    fn first_tile(&self) -> usize {
+        match self {
+            BuildingKind::Hut => 0,
+            BuildingKind::Dock => 4,
+            BuildingKind::Fence => 7,
+            BuildingKind::Shed => 8,
+            BuildingKind::DryingRack => 14,
+        }
+    }
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Building {
+    pub kind: BuildingKind,
+    /// Top left tile of its footprint.
+    pub position: (u16, u16),
+}
+
+impl Building {
+    pub fn new(kind: BuildingKind, position: (u16, u16)) -> Self {
+        Self { kind, position }
+    }
+
+    /// Every tile of its footprint, row by row.
+    pub fn tiles(&self) -> impl Iterator<Item = (u16, u16)> {
+        let (x, y) = self.position;
+        let (width, height) = self.kind.footprint();
+        return (0..height).flat_map(move |dy| (0..width).map(move |dx| (x + dx, y + dy)));
+    }
+
+    pub fn covers(&self, point: (u16, u16)) -> bool {
+        let (width, height) = self.kind.footprint();
+        return (self.position.0..self.position.0 + width).contains(&point.0) && (self.position.1..self.position.1 + height).contains(&point.1);
+    }
+
+    /// Tile in `buildings.aseprite` drawn on a point it covers.
+    pub fn tile(&self, point: (u16, u16)) -> usize {
+        let (width, _) = self.kind.footprint();
+        let (dx, dy) = (point.0 - self.position.0, point.1 - self.position.1);
+        return self.kind.first_tile() + (dy * width + dx) as usize;
+    }
+}
@@ -0,0 +1,39 @@
+use serde::{Deserialize, Serialize};
+
+/// Steps of burning time given by every plank thrown into the fire.
+pub const FUEL_PER_PLANK: u8 = 20;
+const MAX_FUEL: u8 = 240;
+
+#[derive(Copy, Clone, Serialize, Deserialize)]
+pub struct Campfire {
+    pub position: (u16, u16),
+    fuel: u8,
+}
+
+impl Campfire {
+    pub fn new(position: (u16, u16), planks: u8) -> Self {
+        Self { position, fuel: planks.saturating_mul(FUEL_PER_PLANK).min(MAX_FUEL) }
+    }
+
+    pub fn fuel(&self) -> u8 {
+        return self.fuel;
+    }
+
+    pub fn is_lit(&self) -> bool {
+        return self.fuel > 0;
+    }
+
+    /// Returns false if the fire can't take any more fuel.
+    pub fn add_plank(&mut self) -> bool {
+        if self.fuel > MAX_FUEL - FUEL_PER_PLANK {
+            return false;
+        }
+        self.fuel += FUEL_PER_PLANK;
+        return true;
+    }
+
+    /// Called once every step, burns through the fuel.
+    pub fn tick(&mut self) {
+        self.fuel = self.fuel.saturating_sub(1);
+    }
+}
@@ -0,0 +1,110 @@
+use alloc::vec::Vec;
+use serde::ser::SerializeSeq;
+use serde::{Deserialize, Serialize, Serializer};
+use crate::{MAPHEIGHT, MAPWIDTH, ROCKCOUNT, TREECOUNT};
+use crate::bridge::DURABILITY_BITS;
+use crate::building::{Building, MAX_BUILDINGS};
+use crate::campfire::Campfire;
+use crate::drift::{Drifting, MAX_DRIFTING};
+use crate::earthwork::EARTHWORK_BITS;
+use crate::fishstock::{FishStock, RIVER_SEGMENTS};
+use crate::rock::Rock;
+use crate::trap::{Trap, MAX_TRAPS};
+use crate::tree::Tree;
+use crate::worldgen::WorldGenParams;
+
+/// Rows of river generated at a time, the map holds `LOADED_CHUNKS` of them around the player. Every chunk has room for
+/// `TREES_PER_CHUNK` trees, generated forests leave some of it for the trees that spread later, and `ROCKS_PER_CHUNK`
+/// rocks.
+pub const CHUNK_HEIGHT: usize = 16;
+pub const LOADED_CHUNKS: usize = MAPHEIGHT / CHUNK_HEIGHT;
+pub const TREES_PER_CHUNK: usize = TREECOUNT / LOADED_CHUNKS;
+pub const ROCKS_PER_CHUNK: usize = ROCKCOUNT / LOADED_CHUNKS;
+pub const SEGMENTS_PER_CHUNK: usize = RIVER_SEGMENTS / LOADED_CHUNKS;
+/// Chunks the player changed that are kept around once they're unloaded. When it runs out the furthest one with nothing
+/// placed in it is forgotten, and nothing more can be placed once chunks holding placed things would fill it.
+pub const MAX_SAVED_CHUNKS: usize = 16;
+
+// bridges are stored as one bit per column
+const _: () = assert!(MAPWIDTH <= 64);
+// serde only handles arrays of up to 32 elements
+const _: () = assert!(TREES_PER_CHUNK <= 32 && ROCKS_PER_CHUNK <= 32);
+
+/// Everything the player can change in a chunk, positions are relative to the chunk's first row.
+#[derive(Serialize, Deserialize, Copy, Clone)]
+pub struct ChunkData {
+    pub index: u32,
+    /// Durability of the bridges one bit at a time, each bit of it takes up one set of rows.
+    pub bridge: [[u64; CHUNK_HEIGHT]; DURABILITY_BITS],
+    /// Tiles dug out or filled in with the shovel, stored the same way as the bridges.
+    pub earthworks: [[u64; CHUNK_HEIGHT]; EARTHWORK_BITS],
+    pub trees: [Option<Tree>; TREES_PER_CHUNK],
+    pub tree_fruit: [Option<u8>; TREES_PER_CHUNK],
+    pub rocks: [Option<Rock>; ROCKS_PER_CHUNK],
+    pub fish_stock: [FishStock; SEGMENTS_PER_CHUNK],
+    pub traps: [Option<Trap>; MAX_TRAPS],
+    pub campfire: Option<Campfire>,
+    pub buildings: [Option<Building>; MAX_BUILDINGS],
+    pub drifting: [Option<Drifting>; MAX_DRIFTING],
+}
+
+impl ChunkData {
+    /// Whether the chunk is the same as when it was generated, in which case there's no need to keep it.
+    pub fn is_pristine(&self, generated: &ChunkData) -> bool {
+        let same_stock = self.fish_stock.iter().zip(generated.fish_stock.iter()).all(|(a, b)| a.density() == b.density());
+        return self.bridge == generated.bridge
+            && self.earthworks == generated.earthworks
+            && self.trees == generated.trees
+            && self.tree_fruit == generated.tree_fruit
+            && self.rocks == generated.rocks
+            && same_stock
+            && self.traps.iter().all(|trap| trap.is_none())
+            && self.campfire.is_none()
+            && self.buildings.iter().all(|building| building.is_none())
+            && self.drifting.iter().all(|drifting| drifting.is_none());
+    }
+
+    /// Whether the player put anything in it: bridges, earthworks, traps, a campfire, buildings or things afloat.
+    pub fn has_placed(&self) -> bool {
+        return self.bridge.iter().flatten().any(|&bits| bits != 0)
+            || self.earthworks.iter().flatten().any(|&bits| bits != 0)
+            || self.traps.iter().any(|trap| trap.is_some())
+            || self.campfire.is_some()
+            || self.buildings.iter().any(|building| building.is_some())
+            || self.drifting.iter().any(|drifting| drifting.is_some());
+    }
+}
+
+/// The saved part of the world: how it was generated, where the player is, and the chunks that were changed.
+#[derive(Serialize, Deserialize, Clone)]
+pub struct WorldSave {
+    pub seed: u64,
+    pub params: WorldGenParams,
+    pub origin_chunk: u32,
+    pub chunks: Vec<ChunkData>,
+}
+
+/// A `WorldSave` to be written, borrowing the saved chunks instead of copying them. It's read back as a `WorldSave`.
+#[derive(Serialize)]
+pub struct WorldSaveRef<'a> {
+    pub seed: u64,
+    pub params: WorldGenParams,
+    pub origin_chunk: u32,
+    pub chunks: SavedChunks<'a>,
+}
+
+/// The chunks that were unloaded followed by the loaded ones that were changed, written as one list.
+pub struct SavedChunks<'a> {
+    pub saved: &'a [ChunkData],
+    pub loaded: [Option<ChunkData>; LOADED_CHUNKS],
+}
+
+impl Serialize for SavedChunks<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut seq = serializer.serialize_seq(Some(self.saved.len() + self.loaded.iter().flatten().count()))?;
+        for chunk in self.saved.iter().chain(self.loaded.iter().flatten()) {
+            seq.serialize_element(chunk)?;
+        }
+        return seq.end();
+    }
+}
@@ -0,0 +1,18 @@
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum TimeOfDay {
+    Dawn,
+    Day,
+    Dusk,
+    Night,
+}
+
+impl TimeOfDay {
+    pub fn from_hour(hour: usize) -> Self {
+        match hour {
+            5..=6 => TimeOfDay::Dawn,
+            7..=17 => TimeOfDay::Day,
+            18..=19 => TimeOfDay::Dusk,
+            _ => TimeOfDay::Night,
+        }
+    }
+}
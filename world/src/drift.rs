@@ -0,0 +1,27 @@
+use serde::{Deserialize, Serialize};
+use crate::inventory::ItemType;
+
+/// Things floating on the river at once, including the ones in chunks that aren't loaded.
+pub const MAX_DRIFTING: usize = 8;
+
+/// Something carried downstream by the current, a felled log or the wreckage of a bridge, until it's fished out with A.
+#[derive(Copy, Clone, Serialize, Deserialize)]
+pub struct Drifting {
+    pub position: (u16, u16),
+    pub item: ItemType,
+    pub count: u8,
+}
+
+impl Drifting {
+    pub fn new(position: (u16, u16), item: ItemType, count: u8) -> Self {
+        Self { position, item, count }
+    }
+
+    /// Frame in `drift.aseprite`, wood floats as a log and anything else in a crate.
+    pub fn frame(&self) -> usize {
+        match self.item {
+            ItemType::WoodPlank => 0,
+            _ => 1,
+        }
+    }
+}
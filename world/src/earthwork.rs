@@ -0,0 +1,49 @@
+use crate::terrain::Terrain;
+
+/// Bits every tile's earthwork takes up in a saved chunk.
+pub const EARTHWORK_BITS: usize = 2;
+
+/// How the player reshaped a tile with the shovel, on top of the terrain it was generated with.
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Default)]
+pub enum Earthwork {
+    #[default]
+    None,
+    /// Land dug out into shallow water.
+    Dug,
+    /// Shallow water filled in with earth.
+    Filled,
+    /// Rock broken up with a pickaxe.
+    Quarried,
+}
+
+const _: () = assert!((Earthwork::Quarried as usize) < 1 << EARTHWORK_BITS);
+
+impl Earthwork {
+    /// What the shovel does to a tile of this terrain, `None` if it can't be dug or filled.
+    pub fn of(terrain: Terrain) -> Option<Earthwork> {
+        match terrain {
+            Terrain::Grass | Terrain::SandBank | Terrain::Mud => Some(Earthwork::Dug),
+            Terrain::ShallowWater => Some(Earthwork::Filled),
+            Terrain::DeepWater | Terrain::Rock => None,
+        }
+    }
+
+    /// Terrain a tile generated as `terrain` ends up as.
+    pub fn apply(&self, terrain: Terrain) -> Terrain {
+        match self {
+            Earthwork::None => terrain,
+            Earthwork::Dug => Terrain::ShallowWater,
+            Earthwork::Filled => Terrain::Mud,
+            Earthwork::Quarried => Terrain::Grass,
+        }
+    }
+
+    pub fn from_bits(bits: u8) -> Self {
+        match bits {
+            1 => Earthwork::Dug,
+            2 => Earthwork::Filled,
+            3 => Earthwork::Quarried,
+            _ => Earthwork::None,
+        }
+    }
+}
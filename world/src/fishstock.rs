@@ -0,0 +1,98 @@
+use rand::Rng;
+use rand_xoshiro::SplitMix64;
+use serde::{Deserialize, Serialize};
+use crate::daytime::TimeOfDay;
+
+/// One stock per segment of the loaded river, every chunk is split into the same number of segments.
+pub const RIVER_SEGMENTS: usize = 6;
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Default, Serialize, Deserialize)]
+pub enum FishSpecies {
+    #[default]
+    Trout,
+    Carp,
+    Catfish,
+}
+
+impl FishSpecies {
+    pub fn points(&self) -> u32 {
+        match self {
+            FishSpecies::Trout => 10,
+            FishSpecies::Carp => 8,
+            FishSpecies::Catfish => 15,
+        }
+    }
+
+    /// Trout only feed in daylight and catfish come out at night, carp don't care.
+    fn is_active(&self, time_of_day: TimeOfDay) -> bool {
+        match self {
+            FishSpecies::Trout => time_of_day != TimeOfDay::Night,
+            FishSpecies::Carp => true,
+            FishSpecies::Catfish => time_of_day != TimeOfDay::Day,
+        }
+    }
+}
+
+#[derive(Copy, Clone, Default, Serialize, Deserialize)]
+pub struct FishStock {
+    species: FishSpecies,
+    density: u8,
+    capacity: u8,
+    regen_steps: u8,
+    steps: u8,
+}
+
+impl FishStock {
+    pub fn gen(mix64: &mut SplitMix64) -> Self {
+        let species = match mix64.gen::<u8>() % 3 {
+            0 => FishSpecies::Trout,
+            1 => FishSpecies::Carp,
+            _ => FishSpecies::Catfish,
+        };
+        let capacity = mix64.gen_range(10..=20);
+        Self {
+            species,
+            density: capacity,
+            capacity,
+            regen_steps: mix64.gen_range(20..=40),
+            steps: 0,
+        }
+    }
+
+    pub fn species(&self) -> FishSpecies {
+        return self.species;
+    }
+
+    pub fn density(&self) -> u8 {
+        return self.density;
+    }
+
+    /// Chance out of 100 of getting a bite, an empty stretch of river never bites.
+    pub fn bite_chance(&self, time_of_day: TimeOfDay) -> u8 {
+        if self.density == 0 {
+            return 0;
+        }
+        let chance = 10 + (80 * self.density as u16 / self.capacity as u16) as u8;
+        if self.species.is_active(time_of_day) {
+            return chance;
+        }
+        return chance / 4;
+    }
+
+    pub fn take(&mut self) {
+        self.density = self.density.saturating_sub(1);
+    }
+
+    /// Called once every step, a depleted stretch slowly recovers up to its capacity.
+    pub fn tick(&mut self) {
+        if self.density >= self.capacity {
+            self.steps = 0;
+            return;
+        }
+        self.steps += 1;
+        if self.steps >= self.regen_steps {
+            self.steps = 0;
+            self.density += 1;
+        }
+    }
+}
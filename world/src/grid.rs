@@ -0,0 +1,56 @@
+use alloc::boxed::Box;
+use alloc::vec;
+use crate::{MAPHEIGHT, MAPWIDTH};
+
+const GRID_SIZE: usize = MAPWIDTH * MAPHEIGHT;
+
+/// One value per map tile, stored row by row. The cells are boxed, so they're allocated in EWRAM and don't take up the
+/// little stack there is in IWRAM.
+#[derive(Clone)]
+pub struct Grid<T: Copy> {
+    cells: Box<[T]>,
+}
+
+impl<T: Copy + Default> Default for Grid<T> {
+    fn default() -> Self {
+        Self { cells: vec![T::default(); GRID_SIZE].into_boxed_slice() }
+    }
+}
+
+impl<T: Copy + Default> Grid<T> {
+    pub fn in_bounds(point: (usize, usize)) -> bool {
+        return point.0 < MAPWIDTH && point.1 < MAPHEIGHT;
+    }
+
+    pub fn get(&self, point: (usize, usize)) -> T {
+        let (x, y) = point;
+        if !Self::in_bounds(point) {
+            return T::default();
+        }
+        return self.cells[y * MAPWIDTH + x];
+    }
+
+    pub fn set(&mut self, point: (usize, usize), value: T) {
+        let (x, y) = point;
+        if !Self::in_bounds(point) {
+            panic!("Out of bounds!!! {:?}", (x, y));
+        }
+        self.cells[y * MAPWIDTH + x] = value;
+    }
+
+    pub fn fill(&mut self, value: T) {
+        self.cells.fill(value);
+    }
+
+    /// Moves every row up by `rows` (or down, when `forward` is false), filling the rows left behind with defaults.
+    pub fn shift_rows(&mut self, rows: usize, forward: bool) {
+        let offset = rows * MAPWIDTH;
+        if forward {
+            self.cells.copy_within(offset.., 0);
+            self.cells[GRID_SIZE - offset..].fill(T::default());
+        } else {
+            self.cells.copy_within(..GRID_SIZE - offset, offset);
+            self.cells[..offset].fill(T::default());
+        }
+    }
+}
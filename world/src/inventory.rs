@@ -5,6 +5,17 @@ use serde::{Deserialize, Serialize};
 pub enum ItemType {
     WoodPlank,
     Fish,
+    FishingRod,
+    Net,
+    Axe,
+    Raft,
+    FishTrap,
+    CookedFish,
+    Fruit,
+    Shovel,
+    Pickaxe,
+    Stone,
+    StoneAxe,
 }
 
 impl ItemType {
@@ -0,0 +1,32 @@
+#![feature(variant_count)]
+#![no_std]
+
+//! The river, the land around it and everything placed on it, kept apart from agb so it builds and tests on the host.
+
+extern crate alloc;
+
+pub mod autotile;
+pub mod bridge;
+pub mod building;
+pub mod campfire;
+pub mod chunk;
+pub mod daytime;
+pub mod drift;
+pub mod earthwork;
+pub mod fishstock;
+pub mod grid;
+pub mod inventory;
+pub mod map;
+pub mod rock;
+pub mod spawn;
+pub mod terrain;
+pub mod trap;
+pub mod tree;
+pub mod worldgen;
+
+/// Tree slots across the loaded chunks.
+pub const TREECOUNT: usize = 96;
+/// Rock slots across the loaded chunks.
+pub const ROCKCOUNT: usize = 24;
+pub const MAPWIDTH: usize = 64;
+pub const MAPHEIGHT: usize = 48;
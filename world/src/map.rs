@@ -0,0 +1,1343 @@
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+use rand_xoshiro::SplitMix64;
+use core::fmt::{Debug, Formatter, Write};
+use core::ops::Range;
+use rand::{Rng, SeedableRng};
+use crate::{MAPHEIGHT, MAPWIDTH, ROCKCOUNT, TREECOUNT};
+use crate::autotile;
+use crate::bridge::{BRIDGE_DURABILITY, DURABILITY_BITS, STONE_BRIDGE};
+use crate::building::{Building, MAX_BUILDINGS};
+use crate::campfire::Campfire;
+use crate::drift::{Drifting, MAX_DRIFTING};
+use crate::earthwork::{Earthwork, EARTHWORK_BITS};
+use crate::chunk::{ChunkData, SavedChunks, WorldSave, WorldSaveRef, CHUNK_HEIGHT, LOADED_CHUNKS, MAX_SAVED_CHUNKS, ROCKS_PER_CHUNK, SEGMENTS_PER_CHUNK, TREES_PER_CHUNK};
+use crate::fishstock::{FishStock, RIVER_SEGMENTS};
+use crate::grid::Grid;
+use crate::inventory::ItemType;
+use crate::rock::{Rock, RockKind};
+use crate::spawn::Spawn;
+use crate::terrain::Terrain;
+use crate::trap::{Trap, MAX_TRAPS};
+use crate::tree::{GrowthStage, Tree};
+use crate::worldgen::{WorldGenParams, SEED_MASK};
+
+/// Points the river is drawn through in every chunk.
+const RIVER_POINTS: usize = 5;
+/// Rows around a newly loaded chunk that may change when the flood is spread over it.
+const FLOOD_MARGIN: usize = 2;
+/// How much further than its shallow water the river flows, an island pushes its banks out by up to two tiles.
+const CURRENT_MARGIN: usize = 2;
+/// One in this many grown trees drops a seed every step, a sapling sprouts up to `SEED_RANGE` tiles away.
+const SEED_CHANCE: u16 = 1000;
+const SEED_RANGE: i16 = 2;
+/// A new game starts on the land closest to the middle of the river in the second chunk, early enough that the map
+/// doesn't move straight away.
+const SPAWN_ORIGIN: (usize, usize) = (MAPWIDTH / 2, CHUNK_HEIGHT + CHUNK_HEIGHT / 2);
+const SPAWN_ROWS: usize = 2 * CHUNK_HEIGHT;
+
+type ChunkTerrain = [[Terrain; MAPWIDTH]; CHUNK_HEIGHT];
+type ChunkFlow = [Flow; CHUNK_HEIGHT];
+
+/// Tile sets the map is drawn from.
+pub enum Tileset {
+    Map,
+    Shore,
+    Buildings,
+}
+
+/// Leftmost and rightmost column of the river's centerline in every row of a chunk.
+type Centerline = [(usize, usize); CHUNK_HEIGHT];
+
+/// How the river's current runs through a row: its centerline, and which way it bends towards the next row.
+#[derive(Copy, Clone, Default)]
+struct Flow {
+    left: u8,
+    right: u8,
+    dx: i8,
+}
+
+/// Kept boxed, so that it lives in EWRAM along with its grids and saved chunks instead of on the stack.
+#[derive(Clone)]
+pub struct MapData {
+    seed: u64,
+    params: WorldGenParams,
+    /// World chunk shown in the map's first row, the map only holds the chunks around the player.
+    origin_chunk: u32,
+    pub map: Grid<Terrain>,
+    /// Durability left of the bridge on every tile, 0 where there's none and `STONE_BRIDGE` under stone bridges.
+    pub bridge: Grid<u8>,
+    /// Tiles the player reshaped with the shovel, already applied to `map`.
+    pub earthworks: Grid<Earthwork>,
+    /// The current in every row, see `current`.
+    flow: [Flow; MAPHEIGHT],
+    /// Land the river temporarily spilled over during rain, on top of `map`.
+    pub flooded: Grid<bool>,
+    /// Every tree slot of every loaded chunk, a chunk's slots start at its slot times `TREES_PER_CHUNK`.
+    pub trees: [Option<Tree>; TREECOUNT],
+    /// Steps until the matching tree bears fruit again, `None` for trees that never do.
+    pub tree_fruit: [Option<u8>; TREECOUNT],
+    /// Every rock slot of every loaded chunk, laid out the same way as the trees.
+    pub rocks: [Option<Rock>; ROCKCOUNT],
+    pub fish_stock: [FishStock; RIVER_SEGMENTS],
+    pub traps: [Option<Trap>; MAX_TRAPS],
+    pub campfire: Option<Campfire>,
+    pub buildings: [Option<Building>; MAX_BUILDINGS],
+    pub drifting: [Option<Drifting>; MAX_DRIFTING],
+    /// Changed chunks that aren't loaded anymore, at most `MAX_SAVED_CHUNKS` of them.
+    saved_chunks: Vec<ChunkData>,
+    /// How many of the saved chunks have something placed in them.
+    saved_placed: usize,
+    /// Whether each loaded chunk has something placed in it, set when something's placed and looked up again when
+    /// something's removed, so `has_room_for` doesn't have to go through every chunk.
+    placed: [bool; LOADED_CHUNKS],
+    /// Whether each loaded chunk may have changed since it was generated, only those are saved.
+    dirty: [bool; LOADED_CHUNKS],
+}
+
+impl Debug for MapData {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        for y in 0..MAPHEIGHT {
+            for x in 0..MAPWIDTH {
+                f.write_char(match self.get_terrain((x, y)) {
+                    Terrain::DeepWater => '*',
+                    Terrain::ShallowWater => '~',
+                    Terrain::SandBank => '.',
+                    Terrain::Rock => '^',
+                    Terrain::Mud => '%',
+                    Terrain::Grass => '#',
+                })?
+            }
+            f.write_char('\n')?
+        }
+
+        Ok(())
+    }
+}
+
+impl MapData {
+    pub fn get_trees(&self) -> &[Option<Tree>; TREECOUNT] {
+        return &self.trees;
+    }
+    pub fn get_trees_mut(&mut self) -> &mut [Option<Tree>; TREECOUNT] {
+        return &mut self.trees;
+    }
+
+    /// World row shown in the map's first row.
+    pub fn origin_row(&self) -> usize {
+        return self.origin_chunk as usize * CHUNK_HEIGHT;
+    }
+
+    /// Picks the tile for a point from what's built on it and its neighbours.
+    pub fn tile_at(&self, point: (usize, usize), frozen: bool) -> (Tileset, usize) {
+        let tile = (point.0 as u16, point.1 as u16);
+        if let Some(building) = self.building_at(tile).and_then(|index| self.buildings[index]) {
+            return (Tileset::Buildings, building.tile(tile));
+        }
+        let terrain = self.get_terrain(point);
+        let bridges = self.neighbours(point, |neighbour| self.get_bridge_point(neighbour));
+        let land = self.neighbours(point, |neighbour| !self.get_terrain(neighbour).is_water());
+        if self.get_bridge_point(point) {
+            return (Tileset::Shore, autotile::bridge_tile(bridges, land, self.bridge_durability(point)));
+        }
+        if terrain.is_water() {
+            return (Tileset::Shore, autotile::shore_tile(terrain, frozen, land));
+        }
+        if terrain != Terrain::Rock {
+            if let Some(cap) = autotile::bridge_cap_tile(bridges) {
+                return (Tileset::Shore, cap);
+            }
+        }
+        return (Tileset::Map, terrain.tile(frozen));
+    }
+
+    /// Mask of the neighbours matching `predicate`, anything past the edge of the map never does.
+    fn neighbours(&self, point: (usize, usize), predicate: impl Fn((usize, usize)) -> bool) -> u8 {
+        let mut mask = 0;
+        for (bit, (dx, dy)) in autotile::NEIGHBOURS.iter().enumerate() {
+            let neighbour = (point.0.wrapping_add_signed(*dx), point.1.wrapping_add_signed(*dy));
+            if Grid::<Terrain>::in_bounds(neighbour) && predicate(neighbour) {
+                mask |= 1 << bit;
+            }
+        }
+        return mask;
+    }
+
+    /// Generates a new world along with where the player starts in it. A world where no tree can be reached on foot
+    /// gets one planted next to the spawn, and one without anywhere to stand is generated again from another seed.
+    pub fn gen(seed_mix: u64, params: WorldGenParams) -> (Box<Self>, Spawn) {
+        let mut seed = seed_mix;
+        loop {
+            let mut data = Self::load(seed, params, 0, Vec::new());
+            if let Some(spawn) = data.find_spawn().or_else(|| data.plant_spawn()) {
+                debug_assert!(data.check_spawn(&spawn));
+                return (data, spawn);
+            }
+            // kept as short as a typed in seed, so it can be shared all the same
+            seed = (SplitMix64::seed_from_u64(seed).gen::<u32>() & SEED_MASK) as u64;
+        }
+    }
+
+    /// Seed the world is generated from, the same seed always gives the same world.
+    pub fn seed(&self) -> u64 {
+        return self.seed;
+    }
+
+    /// Numbers the world is generated from besides the seed.
+    pub fn params(&self) -> WorldGenParams {
+        return self.params;
+    }
+
+    pub fn from_save(save: WorldSave) -> Box<Self> {
+        return Self::load(save.seed, save.params, save.origin_chunk, save.chunks);
+    }
+
+    /// Only keeps the seed and the chunks that were changed, everything else is generated again on load. The saved
+    /// chunks are borrowed rather than copied.
+    pub fn to_save(&self) -> WorldSaveRef<'_> {
+        let loaded = core::array::from_fn(|slot| self.dirty[slot].then(|| self.extract_chunk(slot)));
+        let chunks = SavedChunks { saved: &self.saved_chunks, loaded };
+        return WorldSaveRef { seed: self.seed, params: self.params, origin_chunk: self.origin_chunk, chunks };
+    }
+
+    fn load(seed: u64, params: WorldGenParams, origin_chunk: u32, saved_chunks: Vec<ChunkData>) -> Box<Self> {
+        let mut data = Box::new(Self {
+            seed,
+            params,
+            origin_chunk,
+            map: Grid::default(),
+            bridge: Grid::default(),
+            earthworks: Grid::default(),
+            flow: [Flow::default(); MAPHEIGHT],
+            flooded: Grid::default(),
+            trees: [None; TREECOUNT],
+            tree_fruit: [None; TREECOUNT],
+            rocks: [None; ROCKCOUNT],
+            fish_stock: [FishStock::default(); RIVER_SEGMENTS],
+            traps: [None; MAX_TRAPS],
+            campfire: None,
+            buildings: [None; MAX_BUILDINGS],
+            drifting: [None; MAX_DRIFTING],
+            saved_chunks,
+            saved_placed: 0,
+            placed: [false; LOADED_CHUNKS],
+            dirty: [false; LOADED_CHUNKS],
+        });
+        data.count_saved_placed();
+        for slot in 0..LOADED_CHUNKS {
+            data.load_chunk(slot);
+        }
+        return data;
+    }
+
+    /// Where the river crosses from one chunk into the next, so neighbouring chunks line up.
+    fn boundary_point(seed: u64, boundary: u32, wander: u8) -> u8 {
+        let mut mix64 = SplitMix64::seed_from_u64(seed ^ (boundary as u64).wrapping_mul(0x9e3779b97f4a7c15));
+        let offset = wander as i8 - (mix64.gen::<u8>() % (2 * wander).max(1)) as i8;
+        return ((MAPWIDTH / 2) as i8 + offset) as u8;
+    }
+
+    /// Generates a chunk from nothing but the seed and its index, returning its terrain and current alongside what the
+    /// player can change.
+    fn gen_chunk(&self, index: u32) -> (ChunkTerrain, ChunkFlow, ChunkData) {
+        let mut mix64 = SplitMix64::seed_from_u64(0x7af07af07af07af0u64 ^ self.seed ^ (index as u64 + 1).wrapping_mul(0xbf58476d1ce4e5b9));
+        let mut points: [u8; RIVER_POINTS] = [0; RIVER_POINTS];
+        points[0] = Self::boundary_point(self.seed, index, self.params.river_wander);
+        points[RIVER_POINTS - 1] = Self::boundary_point(self.seed, index + 1, self.params.river_wander);
+
+        let from = 0;
+        let to = RIVER_POINTS - 1;
+
+        Self::evaluate_midpoint(from, to, &mut points, self.params.midpoint_noise, &mut mix64);
+
+        let centerline = Self::trace_river(points);
+        let mut chance = |percent: u8| mix64.gen::<u8>() % 100 < percent;
+        let island = chance(self.params.features.island_chance);
+        let tributary = chance(self.params.features.tributary_chance);
+        let lake = chance(self.params.features.lake_chance);
+        let oxbow = chance(self.params.features.oxbow_chance);
+        let island = if island {
+            let start = mix64.gen_range(2..CHUNK_HEIGHT - 9);
+            Some((start, start + mix64.gen_range(4..=7)))
+        } else { None };
+        let mut terrain = Self::draw_river(&centerline, island, &self.params, &mut mix64);
+        if tributary {
+            Self::draw_tributary(&mut terrain, &centerline, &mut mix64);
+        }
+        if lake {
+            Self::draw_lake(&mut terrain, &centerline, &self.params, &mut mix64);
+        }
+        if oxbow {
+            Self::draw_oxbow(&mut terrain, &centerline, &self.params, &mut mix64);
+        }
+
+        let mut chunk = ChunkData {
+            index,
+            bridge: [[0; CHUNK_HEIGHT]; DURABILITY_BITS],
+            earthworks: [[0; CHUNK_HEIGHT]; EARTHWORK_BITS],
+            trees: [None; TREES_PER_CHUNK],
+            tree_fruit: [None; TREES_PER_CHUNK],
+            rocks: [None; ROCKS_PER_CHUNK],
+            fish_stock: [(); SEGMENTS_PER_CHUNK].map(|_| FishStock::gen(&mut mix64)),
+            traps: [None; MAX_TRAPS],
+            campfire: None,
+            buildings: [None; MAX_BUILDINGS],
+            drifting: [None; MAX_DRIFTING],
+        };
+        Self::plant_forests(&terrain, &mut chunk, &self.params, &mut mix64);
+        Self::place_rocks(&terrain, &mut chunk, &self.params, &mut mix64);
+        let flow = Self::trace_flow(&centerline);
+
+        return (terrain, flow, chunk);
+    }
+
+    /// Grows a few clusters of trees on the chunk's grass, with some lone trees scattered in between.
+    fn plant_forests(terrain: &ChunkTerrain, chunk: &mut ChunkData, params: &WorldGenParams, mix64: &mut SplitMix64) {
+        let range = |(from, to): (u8, u8)| from as usize..=to as usize;
+        let clusters = mix64.gen_range(range(params.forest_clusters));
+        let lone_trees = mix64.gen_range(range(params.lone_trees));
+        let max_trees = (params.max_trees as usize).min(TREES_PER_CHUNK);
+        let mut planted = 0;
+        for group in 0..clusters + lone_trees {
+            let (size, radius) = if group < clusters {
+                (mix64.gen_range(range(params.forest_size)), params.forest_radius as i16)
+            } else { (1, 0) };
+            let centre = (mix64.gen_range(0..MAPWIDTH as i16), mix64.gen_range(0..CHUNK_HEIGHT as i16));
+            let mut grown = 0;
+            // some spots land on water or on another tree, give up on them rather than filling every cluster
+            for _ in 0..size * 2 {
+                if grown == size || planted == max_trees {
+                    break;
+                }
+                let x = centre.0 + mix64.gen_range(-radius..=radius);
+                let y = centre.1 + mix64.gen_range(-radius..=radius);
+                if !(0..MAPWIDTH as i16).contains(&x) || !(0..CHUNK_HEIGHT as i16).contains(&y) {
+                    continue;
+                }
+                let (x, y) = (x as u16, y as u16);
+                let taken = chunk.trees.iter().flatten().any(|tree| tree.position == (x, y));
+                if terrain[y as usize][x as usize] != Terrain::Grass || taken {
+                    continue;
+                }
+                chunk.trees[planted] = Some(Tree::new((x, y), GrowthStage::Mature));
+                if mix64.gen::<u8>() % 3 == 0 {
+                    chunk.tree_fruit[planted] = Some(0);
+                }
+                planted += 1;
+                grown += 1;
+            }
+        }
+    }
+
+    /// Scatters rocks and the odd ore deposit over the chunk's dry land, out of the way of its trees.
+    fn place_rocks(terrain: &ChunkTerrain, chunk: &mut ChunkData, params: &WorldGenParams, mix64: &mut SplitMix64) {
+        let (from, to) = params.rocks;
+        let count = mix64.gen_range(from as usize..=to as usize).min(ROCKS_PER_CHUNK);
+        let mut placed = 0;
+        for _ in 0..count * 4 {
+            if placed == count {
+                break;
+            }
+            let (x, y) = (mix64.gen_range(0..MAPWIDTH as u16), mix64.gen_range(0..CHUNK_HEIGHT as u16));
+            let kind = if mix64.gen::<u8>() % 100 < params.ore_chance { RockKind::Ore } else { RockKind::Boulder };
+            let taken = chunk.trees.iter().flatten().any(|tree| tree.position == (x, y))
+                || chunk.rocks.iter().flatten().any(|rock| rock.position == (x, y));
+            if !matches!(terrain[y as usize][x as usize], Terrain::Grass | Terrain::SandBank | Terrain::Mud) || taken {
+                continue;
+            }
+            chunk.rocks[placed] = Some(Rock::new((x, y), kind));
+            placed += 1;
+        }
+    }
+
+    /// Fills a slot with its chunk, as it was left if the player changed it or freshly generated otherwise.
+    fn load_chunk(&mut self, slot: usize) {
+        let index = self.origin_chunk + slot as u32;
+        let (terrain, flow, generated) = self.gen_chunk(index);
+        let saved = self.saved_chunks.iter().position(|chunk| chunk.index == index);
+        let chunk = saved.map(|saved| self.saved_chunks.swap_remove(saved)).unwrap_or(generated);
+        self.count_saved_placed();
+        self.dirty[slot] = saved.is_some();
+        self.placed[slot] = chunk.has_placed();
+        let row = slot * CHUNK_HEIGHT;
+        self.flow[row..row + CHUNK_HEIGHT].copy_from_slice(&flow);
+        for (y, tiles) in terrain.iter().enumerate() {
+            for (x, &tile) in tiles.iter().enumerate() {
+                let earthwork = Earthwork::from_bits(Self::read_bits(&chunk.earthworks, (x, y)));
+                self.map.set((x, row + y), earthwork.apply(tile));
+                self.earthworks.set((x, row + y), earthwork);
+                self.bridge.set((x, row + y), Self::read_bits(&chunk.bridge, (x, y)));
+            }
+        }
+        let to_map = |(x, y): (u16, u16)| (x, y + row as u16);
+        for i in 0..TREES_PER_CHUNK {
+            self.trees[slot * TREES_PER_CHUNK + i] = chunk.trees[i].map(|mut tree| {
+                tree.position = to_map(tree.position);
+                tree
+            });
+            self.tree_fruit[slot * TREES_PER_CHUNK + i] = chunk.tree_fruit[i];
+        }
+        for i in 0..ROCKS_PER_CHUNK {
+            self.rocks[slot * ROCKS_PER_CHUNK + i] = chunk.rocks[i].map(|mut rock| {
+                rock.position = to_map(rock.position);
+                rock
+            });
+        }
+        self.fish_stock[slot * SEGMENTS_PER_CHUNK..(slot + 1) * SEGMENTS_PER_CHUNK].copy_from_slice(&chunk.fish_stock);
+        // there are never more traps than slots, place_trap counts the ones in saved chunks too
+        for (slot, mut trap) in self.traps.iter_mut().filter(|slot| slot.is_none()).zip(chunk.traps.into_iter().flatten()) {
+            trap.position = to_map(trap.position);
+            *slot = Some(trap);
+        }
+        if let Some(mut campfire) = chunk.campfire {
+            campfire.position = to_map(campfire.position);
+            self.campfire = Some(campfire);
+        }
+        // same as traps, place_building counts the buildings in saved chunks
+        for (slot, mut building) in self.buildings.iter_mut().filter(|slot| slot.is_none()).zip(chunk.buildings.into_iter().flatten()) {
+            building.position = to_map(building.position);
+            *slot = Some(building);
+        }
+        for (slot, mut drifting) in self.drifting.iter_mut().filter(|slot| slot.is_none()).zip(chunk.drifting.into_iter().flatten()) {
+            drifting.position = to_map(drifting.position);
+            *slot = Some(drifting);
+        }
+    }
+
+    /// Copies everything the player can change in a slot's chunk.
+    fn extract_chunk(&self, slot: usize) -> ChunkData {
+        let row = slot * CHUNK_HEIGHT;
+        let rows = row as u16..(row + CHUNK_HEIGHT) as u16;
+        let from_map = |(x, y): (u16, u16)| (x, y - row as u16);
+        let mut chunk = ChunkData {
+            index: self.origin_chunk + slot as u32,
+            bridge: [[0; CHUNK_HEIGHT]; DURABILITY_BITS],
+            earthworks: [[0; CHUNK_HEIGHT]; EARTHWORK_BITS],
+            trees: [None; TREES_PER_CHUNK],
+            tree_fruit: [None; TREES_PER_CHUNK],
+            rocks: [None; ROCKS_PER_CHUNK],
+            fish_stock: [FishStock::default(); SEGMENTS_PER_CHUNK],
+            traps: [None; MAX_TRAPS],
+            campfire: None,
+            buildings: [None; MAX_BUILDINGS],
+            drifting: [None; MAX_DRIFTING],
+        };
+        for y in 0..CHUNK_HEIGHT {
+            for x in 0..MAPWIDTH {
+                Self::write_bits(&mut chunk.bridge, (x, y), self.bridge.get((x, row + y)));
+                Self::write_bits(&mut chunk.earthworks, (x, y), self.earthworks.get((x, row + y)) as u8);
+            }
+        }
+        for i in 0..TREES_PER_CHUNK {
+            chunk.trees[i] = self.trees[slot * TREES_PER_CHUNK + i].map(|mut tree| {
+                tree.position = from_map(tree.position);
+                tree
+            });
+            chunk.tree_fruit[i] = self.tree_fruit[slot * TREES_PER_CHUNK + i];
+        }
+        for i in 0..ROCKS_PER_CHUNK {
+            chunk.rocks[i] = self.rocks[slot * ROCKS_PER_CHUNK + i].map(|mut rock| {
+                rock.position = from_map(rock.position);
+                rock
+            });
+        }
+        chunk.fish_stock.copy_from_slice(&self.fish_stock[slot * SEGMENTS_PER_CHUNK..(slot + 1) * SEGMENTS_PER_CHUNK]);
+        for (saved, mut trap) in chunk.traps.iter_mut().zip(self.traps.iter().flatten().copied().filter(|trap| rows.contains(&trap.position.1))) {
+            trap.position = from_map(trap.position);
+            *saved = Some(trap);
+        }
+        if let Some(mut campfire) = self.campfire.filter(|campfire| rows.contains(&campfire.position.1)) {
+            campfire.position = from_map(campfire.position);
+            chunk.campfire = Some(campfire);
+        }
+        // buildings never straddle two chunks, can_build makes sure of it
+        for (saved, mut building) in chunk.buildings.iter_mut().zip(self.buildings.iter().flatten().copied().filter(|building| rows.contains(&building.position.1))) {
+            building.position = from_map(building.position);
+            *saved = Some(building);
+        }
+        for (saved, mut drifting) in chunk.drifting.iter_mut().zip(self.drifting.iter().flatten().copied().filter(|drifting| rows.contains(&drifting.position.1))) {
+            drifting.position = from_map(drifting.position);
+            *saved = Some(drifting);
+        }
+        return chunk;
+    }
+
+    /// Reads a value stored one bit per set of rows, the way a chunk keeps its bridges and earthworks.
+    fn read_bits(planes: &[[u64; CHUNK_HEIGHT]], (x, y): (usize, usize)) -> u8 {
+        return planes.iter().enumerate().fold(0, |value, (bit, rows)| value | (((rows[y] >> x) & 1) as u8) << bit);
+    }
+
+    fn write_bits(planes: &mut [[u64; CHUNK_HEIGHT]], (x, y): (usize, usize), value: u8) {
+        for (bit, rows) in planes.iter_mut().enumerate() {
+            if (value >> bit) & 1 != 0 {
+                rows[y] |= 1 << x;
+            }
+        }
+    }
+
+    /// Keeps a changed chunk, making room by forgetting the one furthest away from it.
+    fn store_chunk(&mut self, chunk: ChunkData, generated: &ChunkData) {
+        let chunks = &mut self.saved_chunks;
+        let existing = chunks.iter().position(|saved| saved.index == chunk.index);
+        if chunk.is_pristine(generated) {
+            if let Some(existing) = existing {
+                chunks.swap_remove(existing);
+            }
+            return;
+        }
+        if existing.is_none() && chunks.len() < MAX_SAVED_CHUNKS {
+            chunks.push(chunk);
+            return;
+        }
+        // chunks with something placed in them are never forgotten, `has_room_for` keeps a slot free for each of them
+        let slot = existing.or_else(|| (0..chunks.len()).filter(|&i| !chunks[i].has_placed()).max_by_key(|&i| chunks[i].index.abs_diff(chunk.index)));
+        if let Some(slot) = slot {
+            chunks[slot] = chunk;
+        }
+    }
+
+    fn count_saved_placed(&mut self) {
+        self.saved_placed = self.saved_chunks.iter().filter(|chunk| chunk.has_placed()).count();
+    }
+
+    /// Notes that something at a point changed from how it was generated, so its chunk gets saved.
+    pub fn mark_changed(&mut self, point: (u16, u16)) {
+        if let Some(dirty) = self.dirty.get_mut(point.1 as usize / CHUNK_HEIGHT) {
+            *dirty = true;
+        }
+    }
+
+    /// Notes that something was placed at a point.
+    fn mark_placed(&mut self, point: (u16, u16)) {
+        self.mark_changed(point);
+        if let Some(placed) = self.placed.get_mut(point.1 as usize / CHUNK_HEIGHT) {
+            *placed = true;
+        }
+    }
+
+    /// Notes that something was removed from a point, looking through its chunk for anything else placed in it.
+    fn mark_removed(&mut self, point: (u16, u16)) {
+        self.mark_changed(point);
+        let slot = point.1 as usize / CHUNK_HEIGHT;
+        if slot >= LOADED_CHUNKS {
+            return;
+        }
+        let rows = (slot * CHUNK_HEIGHT) as u16..((slot + 1) * CHUNK_HEIGHT) as u16;
+        let in_chunk = |position: (u16, u16)| rows.contains(&position.1);
+        let tiles = rows.clone().any(|y| (0..MAPWIDTH).any(|x| self.bridge.get((x, y as usize)) != 0 || self.earthworks.get((x, y as usize)) != Earthwork::None));
+        self.placed[slot] = tiles
+            || self.traps.iter().flatten().any(|trap| in_chunk(trap.position))
+            || self.campfire.is_some_and(|campfire| in_chunk(campfire.position))
+            || self.buildings.iter().flatten().any(|building| in_chunk(building.position))
+            || self.drifting.iter().flatten().any(|drifting| in_chunk(drifting.position));
+    }
+
+    /// Whether something can be placed at a point without more chunks holding placed things than can be saved, so none
+    /// of them has to be forgotten once it's unloaded.
+    pub fn has_room_for(&self, point: (usize, usize)) -> bool {
+        if self.placed[point.1 / CHUNK_HEIGHT] {
+            return true;
+        }
+        return self.placed.iter().filter(|&&placed| placed).count() + self.saved_placed < MAX_SAVED_CHUNKS;
+    }
+
+    /// Moves the map a chunk further down the river, or back up it, keeping the chunk that falls off if it was changed.
+    /// Returns the rows that have to be drawn again, including the ones next to the new chunk a flood may spill into.
+    pub fn shift(&mut self, forward: bool) -> Range<usize> {
+        if !forward && self.origin_chunk == 0 {
+            return 0..0;
+        }
+        let (unloaded, loaded) = if forward { (0, LOADED_CHUNKS - 1) } else { (LOADED_CHUNKS - 1, 0) };
+        if self.dirty[unloaded] {
+            let chunk = self.extract_chunk(unloaded);
+            let generated = self.gen_chunk(chunk.index).2;
+            self.store_chunk(chunk, &generated);
+            self.count_saved_placed();
+        }
+
+        let rows = unloaded as u16 * CHUNK_HEIGHT as u16..(unloaded + 1) as u16 * CHUNK_HEIGHT as u16;
+        for trap in self.traps.iter_mut() {
+            if matches!(trap, Some(trap) if rows.contains(&trap.position.1)) {
+                *trap = None;
+            }
+        }
+        if matches!(self.campfire, Some(campfire) if rows.contains(&campfire.position.1)) {
+            self.campfire = None;
+        }
+        for building in self.buildings.iter_mut() {
+            if matches!(building, Some(building) if rows.contains(&building.position.1)) {
+                *building = None;
+            }
+        }
+        for drifting in self.drifting.iter_mut() {
+            if matches!(drifting, Some(drifting) if rows.contains(&drifting.position.1)) {
+                *drifting = None;
+            }
+        }
+
+        let move_row = |y: u16| if forward { y - CHUNK_HEIGHT as u16 } else { y + CHUNK_HEIGHT as u16 };
+        self.map.shift_rows(CHUNK_HEIGHT, forward);
+        self.bridge.shift_rows(CHUNK_HEIGHT, forward);
+        self.earthworks.shift_rows(CHUNK_HEIGHT, forward);
+        self.flooded.shift_rows(CHUNK_HEIGHT, forward);
+        if forward {
+            self.flow.copy_within(CHUNK_HEIGHT.., 0);
+            self.trees.copy_within(TREES_PER_CHUNK.., 0);
+            self.tree_fruit.copy_within(TREES_PER_CHUNK.., 0);
+            self.rocks.copy_within(ROCKS_PER_CHUNK.., 0);
+            self.fish_stock.copy_within(SEGMENTS_PER_CHUNK.., 0);
+            self.placed.copy_within(1.., 0);
+            self.dirty.copy_within(1.., 0);
+            self.origin_chunk += 1;
+        } else {
+            self.flow.copy_within(..MAPHEIGHT - CHUNK_HEIGHT, CHUNK_HEIGHT);
+            self.trees.copy_within(..TREECOUNT - TREES_PER_CHUNK, TREES_PER_CHUNK);
+            self.tree_fruit.copy_within(..TREECOUNT - TREES_PER_CHUNK, TREES_PER_CHUNK);
+            self.rocks.copy_within(..ROCKCOUNT - ROCKS_PER_CHUNK, ROCKS_PER_CHUNK);
+            self.fish_stock.copy_within(..RIVER_SEGMENTS - SEGMENTS_PER_CHUNK, SEGMENTS_PER_CHUNK);
+            self.placed.copy_within(..LOADED_CHUNKS - 1, 1);
+            self.dirty.copy_within(..LOADED_CHUNKS - 1, 1);
+            self.origin_chunk -= 1;
+        }
+        let kept = if forward { 0..TREECOUNT - TREES_PER_CHUNK } else { TREES_PER_CHUNK..TREECOUNT };
+        for tree in self.trees[kept].iter_mut().flatten() {
+            tree.position.1 = move_row(tree.position.1);
+        }
+        let kept = if forward { 0..ROCKCOUNT - ROCKS_PER_CHUNK } else { ROCKS_PER_CHUNK..ROCKCOUNT };
+        for rock in self.rocks[kept].iter_mut().flatten() {
+            rock.position.1 = move_row(rock.position.1);
+        }
+        for trap in self.traps.iter_mut().flatten() {
+            trap.position.1 = move_row(trap.position.1);
+        }
+        if let Some(campfire) = self.campfire.as_mut() {
+            campfire.position.1 = move_row(campfire.position.1);
+        }
+        for building in self.buildings.iter_mut().flatten() {
+            building.position.1 = move_row(building.position.1);
+        }
+        for drifting in self.drifting.iter_mut().flatten() {
+            drifting.position.1 = move_row(drifting.position.1);
+        }
+        self.load_chunk(loaded);
+
+        let start = (loaded * CHUNK_HEIGHT).saturating_sub(FLOOD_MARGIN);
+        return start..((loaded + 1) * CHUNK_HEIGHT + FLOOD_MARGIN).min(MAPHEIGHT);
+    }
+
+    pub fn set_terrain_point(&mut self, point: (usize, usize), terrain: Terrain) {
+        self.map.set(point, terrain);
+    }
+
+    /// Digs land out into shallow water or fills shallow water in with earth, returns false if the tile can't be
+    /// reshaped, something stands on it or there's no room to save it.
+    pub fn reshape(&mut self, point: (usize, usize)) -> bool {
+        let tile = (point.0 as u16, point.1 as u16);
+        if !Grid::<Terrain>::in_bounds(point) || self.get_bridge_point(point) || self.tree_at(tile).is_some() || self.rock_at(tile).is_some() || self.building_at(tile).is_some() || self.trap_at(tile).is_some() || self.has_campfire(tile) || !self.has_room_for(point) {
+            return false;
+        }
+        let Some(earthwork) = Earthwork::of(self.map.get(point)) else {
+            return false;
+        };
+        self.set_terrain_point(point, earthwork.apply(self.map.get(point)));
+        self.earthworks.set(point, earthwork);
+        self.mark_placed(tile);
+        return true;
+    }
+
+    /// Breaks rock terrain up into grass with a pickaxe, returns false if the tile isn't rock or there's no room to save it.
+    pub fn quarry(&mut self, point: (usize, usize)) -> bool {
+        if !Grid::<Terrain>::in_bounds(point) || self.map.get(point) != Terrain::Rock || !self.has_room_for(point) {
+            return false;
+        }
+        self.set_terrain_point(point, Earthwork::Quarried.apply(Terrain::Rock));
+        self.earthworks.set(point, Earthwork::Quarried);
+        self.mark_placed((point.0 as u16, point.1 as u16));
+        return true;
+    }
+
+    /// Builds a bridge with the given durability left, or `STONE_BRIDGE` for a stone one, or removes it with 0.
+    pub fn set_bridge(&mut self, point: (usize, usize), durability: u8) {
+        self.bridge.set(point, durability.min(STONE_BRIDGE));
+        let tile = (point.0 as u16, point.1 as u16);
+        if durability > 0 {
+            self.mark_placed(tile);
+        } else {
+            self.mark_removed(tile);
+        }
+    }
+
+    pub fn bridge_durability(&self, point: (usize, usize)) -> u8 {
+        return self.bridge.get(point);
+    }
+
+    /// Wears a bridge down once, returns true if it broke and dropped back into the river.
+    pub fn wear_bridge(&mut self, point: (usize, usize)) -> bool {
+        let durability = self.bridge.get(point);
+        if durability == 0 || durability == STONE_BRIDGE {
+            return false;
+        }
+        self.set_bridge(point, durability - 1);
+        if durability == 1 {
+            // some of the broken bridge floats off downstream
+            self.set_adrift(Drifting::new((point.0 as u16, point.1 as u16), ItemType::WoodPlank, 1));
+        }
+        return durability == 1;
+    }
+
+    /// Wears down each wooden bridge with a one in `chance` chance, returning the rows that changed if any did.
+    pub fn wear_bridges(&mut self, chance: u16, rng: &mut SplitMix64) -> Option<Range<usize>> {
+        let mut changed: Option<Range<usize>> = None;
+        for y in 0..MAPHEIGHT {
+            for x in 0..MAPWIDTH {
+                if !(1..=BRIDGE_DURABILITY).contains(&self.bridge.get((x, y))) || rng.gen::<u16>() % chance != 0 {
+                    continue;
+                }
+                self.wear_bridge((x, y));
+                changed = Some(changed.map_or(y, |rows| rows.start)..y + 1);
+            }
+        }
+        return changed;
+    }
+
+    /// Terrain at a point, land under a flood counts as shallow water.
+    pub fn get_terrain(&self, point: (usize, usize)) -> Terrain {
+        if self.flooded.get(point) {
+            return Terrain::ShallowWater;
+        }
+        return self.map.get(point);
+    }
+
+    /// Spills the river `level` tiles over its banks, keeping trees, the campfire and `dry` out of the water.
+    pub fn set_flood_level(&mut self, level: u8, dry: (u16, u16)) {
+        self.flooded.fill(false);
+        for _ in 0..level {
+            let previous = self.flooded.clone();
+            let is_water = |x: usize, y: usize| self.map.get((x, y)).is_water() || previous.get((x, y));
+            for y in 0..MAPHEIGHT {
+                for x in 0..MAPWIDTH {
+                    // out of bounds neighbours wrap to usize::MAX and read as land
+                    let grown = is_water(x.wrapping_sub(1), y) || is_water(x + 1, y) || is_water(x, y.wrapping_sub(1)) || is_water(x, y + 1);
+                    if grown && self.map.get((x, y)).can_flood() {
+                        self.flooded.set((x, y), true);
+                    }
+                }
+            }
+        }
+        let campfire = self.campfire.map(|campfire| campfire.position);
+        let buildings = self.buildings.iter().flatten().flat_map(|building| building.tiles());
+        for (x, y) in self.trees.iter().flatten().map(|tree| tree.position).chain(campfire).chain(buildings).chain(core::iter::once(dry)) {
+            self.flooded.set((x as usize, y as usize), false);
+        }
+    }
+
+    pub fn get_bridge_point(&self, point: (usize, usize)) -> bool {
+        return self.bridge.get(point) > 0;
+    }
+
+    /// Draws the river's centerline through the points.
+    fn trace_river<const COUNT: usize>(river_points: [u8; COUNT]) -> Centerline {
+        let mut centerline = [(MAPWIDTH, 0); CHUNK_HEIGHT];
+        for i in 0..COUNT - 1 {
+            let x1 = river_points[i] as usize;
+            let y1 = (CHUNK_HEIGHT * i) / (COUNT - 1);
+
+            let x2 = river_points[i + 1] as usize;
+            let y2 = (CHUNK_HEIGHT * (i + 1)) / (COUNT - 1);
+
+
+            for (x, y) in bresenham::Bresenham::new((x1 as isize, y1 as isize), (x2 as isize, y2 as isize)) {
+                // the last row belongs to the next chunk
+                if let Some((left, right)) = centerline.get_mut(y as usize) {
+                    *left = (*left).min(x as usize);
+                    *right = (*right).max(x as usize);
+                }
+            }
+        }
+        return centerline;
+    }
+
+    /// Lays the depth bands and shores around the centerline. Within the island's rows the river splits around a strip of land.
+    fn draw_river(centerline: &Centerline, island: Option<(usize, usize)>, params: &WorldGenParams, mix64: &mut SplitMix64) -> ChunkTerrain {
+        let (deep, shallow, bank) = (params.deep_water_range.map(usize::from), params.shallow_water_range as usize, params.sand_bank_range as usize);
+        let mut terrain = [[Terrain::Grass; MAPWIDTH]; CHUNK_HEIGHT];
+        for (y, (row, &(left, right))) in terrain.iter_mut().zip(centerline.iter()).enumerate() {
+            // the island is pointy at both ends
+            let island_radius = match island {
+                Some((start, end)) if y == start || y == end => Some(0),
+                Some((start, end)) if y > start && y < end => Some(1),
+                _ => None,
+            };
+            for (x, tile) in row.iter_mut().enumerate() {
+                let distance = if x < left { left - x } else { x.saturating_sub(right) };
+                let roll = mix64.gen::<u8>() % 100;
+                let band = match island_radius {
+                    Some(radius) if distance <= radius => Some(if distance < radius { Terrain::Grass } else { Terrain::SandBank }),
+                    Some(radius) => Self::band(distance - radius - 1, deep, shallow, bank),
+                    None => Self::band(distance, deep, shallow, bank),
+                };
+                *tile = if let Some(band) = band {
+                    band
+                } else if distance <= params.mud_range as usize && roll < 20 {
+                    Terrain::Mud
+                } else if roll < 3 {
+                    Terrain::Rock
+                } else {
+                    Terrain::Grass
+                };
+            }
+        }
+        return terrain;
+    }
+
+    /// How the current runs through every row of a chunk, downstream along the centerline and towards its next bend.
+    fn trace_flow(centerline: &Centerline) -> ChunkFlow {
+        let middle = |y: usize| ((centerline[y].0 + centerline[y].1) / 2) as isize;
+        return core::array::from_fn(|y| {
+            // the last row keeps the bend of the one above it, the next chunk's first row lines up with it
+            let (from, to) = if y + 1 < CHUNK_HEIGHT { (y, y + 1) } else { (y - 1, y) };
+            let (left, right) = centerline[y];
+            return Flow { left: left as u8, right: right as u8, dx: (middle(to) - middle(from)).signum() as i8 };
+        });
+    }
+
+    /// Way the water flows on a tile, one step downstream. It's (0, 0) on land and on still water away from the river,
+    /// in lakes, ponds and oxbows.
+    pub fn current(&self, point: (usize, usize)) -> (i8, i8) {
+        if !Grid::<Terrain>::in_bounds(point) || !self.map.get(point).is_water() {
+            return (0, 0);
+        }
+        let Flow { left, right, dx } = self.flow[point.1];
+        let (x, left, right) = (point.0, left as usize, right as usize);
+        let distance = if x < left { left - x } else { x.saturating_sub(right) };
+        if distance > self.params.shallow_water_range as usize + CURRENT_MARGIN {
+            return (0, 0);
+        }
+        return (dx, 1);
+    }
+
+    /// Terrain at some distance from the middle of a body of water, `None` once past its shore.
+    fn band(distance: usize, deep: Option<usize>, shallow: usize, bank: usize) -> Option<Terrain> {
+        if deep.is_some_and(|deep| distance <= deep) {
+            return Some(Terrain::DeepWater);
+        } else if distance <= shallow {
+            return Some(Terrain::ShallowWater);
+        } else if distance <= bank {
+            return Some(Terrain::SandBank);
+        }
+        return None;
+    }
+
+    /// Puts water on a tile unless it's already wetter, so features can overlap the river and each other.
+    fn soak(terrain: &mut ChunkTerrain, point: (isize, isize), water: Option<Terrain>) {
+        let (x, y) = point;
+        let Some(water) = water else {
+            return;
+        };
+        if x < 0 || y < 0 || x as usize >= MAPWIDTH || y as usize >= CHUNK_HEIGHT {
+            return;
+        }
+        let tile = &mut terrain[y as usize][x as usize];
+        if water.wetness() > tile.wetness() {
+            *tile = water;
+        }
+    }
+
+    /// The side of the river with more room at a row, -1 for the left and 1 for the right.
+    fn wider_side(centerline: &Centerline, y: usize) -> isize {
+        let (left, right) = centerline[y];
+        return if left > MAPWIDTH - 1 - right { -1 } else { 1 };
+    }
+
+    /// A narrow stream from the edge of the map that flows into the river further down.
+    fn draw_tributary(terrain: &mut ChunkTerrain, centerline: &Centerline, mix64: &mut SplitMix64) {
+        let start_y = mix64.gen_range(0..CHUNK_HEIGHT / 2 - 2);
+        let end_y = start_y + mix64.gen_range(4..=8);
+        let start_x = if Self::wider_side(centerline, start_y) < 0 { 0 } else { MAPWIDTH - 1 };
+        let (left, right) = centerline[end_y];
+        let end_x = (left + right) / 2;
+        for (x, y) in bresenham::Bresenham::new((start_x as isize, start_y as isize), (end_x as isize, end_y as isize)) {
+            for dy in -2isize..=2 {
+                for dx in -2isize..=2 {
+                    let distance = dx.unsigned_abs().max(dy.unsigned_abs());
+                    Self::soak(terrain, (x + dx, y + dy), Self::band(distance, Some(0), 1, 2));
+                }
+            }
+        }
+    }
+
+    /// A pond or a lake on the wider side of the river, far enough from it to stay separate.
+    fn draw_lake(terrain: &mut ChunkTerrain, centerline: &Centerline, params: &WorldGenParams, mix64: &mut SplitMix64) {
+        let radius_x = mix64.gen_range(2..=5isize);
+        let radius_y = mix64.gen_range(2..=3isize);
+        let centre_y = mix64.gen_range(radius_y + 2..CHUNK_HEIGHT as isize - radius_y - 2);
+        let side = Self::wider_side(centerline, centre_y as usize);
+        let (left, right) = centerline[centre_y as usize];
+        let shore = if side < 0 { left as isize } else { right as isize } + side * params.sand_bank_range as isize;
+        let centre_x = shore + side * (radius_x + 3 + mix64.gen_range(0..6isize));
+        // ponds are too small to have deep water
+        let deep = if radius_x >= 3 { Some(6) } else { None };
+        for dy in -radius_y - 2..=radius_y + 2 {
+            for dx in -radius_x - 2..=radius_x + 2 {
+                let scaled = Self::ellipse_distance((dx, dy), (radius_x, radius_y));
+                Self::soak(terrain, (centre_x + dx, centre_y + dy), Self::band(scaled, deep, 16, 28));
+            }
+        }
+    }
+
+    /// A crescent of water hugging the river, the remains of a bend that was cut off.
+    fn draw_oxbow(terrain: &mut ChunkTerrain, centerline: &Centerline, params: &WorldGenParams, mix64: &mut SplitMix64) {
+        let radius = mix64.gen_range(3..=4isize);
+        let centre_y = mix64.gen_range(radius + 2..CHUNK_HEIGHT as isize - radius - 2);
+        let side = Self::wider_side(centerline, centre_y as usize);
+        let (left, right) = centerline[centre_y as usize];
+        let edge = if side < 0 { left as isize } else { right as isize } + side * params.shallow_water_range as isize;
+        let centre_x = edge + side * (radius + 1);
+        for dy in -radius - 2..=radius + 2 {
+            // only the half facing away from the river
+            for dx in (0..=radius + 2).map(|dx| dx * side) {
+                let scaled = Self::ellipse_distance((dx, dy), (radius, radius));
+                let water = match scaled {
+                    13..=17 => Some(Terrain::DeepWater),
+                    9..=22 => Some(Terrain::ShallowWater),
+                    6..=28 => Some(Terrain::SandBank),
+                    _ => None,
+                };
+                Self::soak(terrain, (centre_x + dx, centre_y + dy), water);
+            }
+        }
+    }
+
+    /// How far an offset is from the centre of an ellipse, scaled so that its edge is at 16.
+    fn ellipse_distance(offset: (isize, isize), radius: (isize, isize)) -> usize {
+        let (dx, dy) = offset;
+        let (rx, ry) = radius;
+        return (dx * dx * 16 / (rx * rx) + dy * dy * 16 / (ry * ry)) as usize;
+    }
+
+    fn evaluate_midpoint<const COUNT: usize>(from: usize, to: usize, points: &mut [u8; COUNT], noise: u8, mix64: &mut SplitMix64) {
+        if to - from <= 1 { return; }
+        let xi: usize = (from + to) / 2;
+
+        let av = points[from];
+        let bv = points[to];
+        let xv = (av + bv) / 2;
+
+        let offset = noise as i8 - (mix64.gen::<u8>() % (2 * noise).max(1)) as i8;
+        let nxv = ((xv as i8) + offset).clamp(1, MAPWIDTH as i8 - 2) as u8;
+
+        points[xi] = nxv;
+        Self::evaluate_midpoint(from, xi, points, noise, mix64);
+        Self::evaluate_midpoint(xi, to, points, noise, mix64);
+    }
+    /// River segment a point belongs to, segments are evenly spread across the map's height.
+    pub fn segment_at(point: (u16, u16)) -> usize {
+        return (point.1 as usize * RIVER_SEGMENTS / MAPHEIGHT).min(RIVER_SEGMENTS - 1);
+    }
+
+    pub fn fish_stock(&self, point: (u16, u16)) -> &FishStock {
+        return &self.fish_stock[Self::segment_at(point)];
+    }
+
+    pub fn fish_stock_mut(&mut self, point: (u16, u16)) -> &mut FishStock {
+        self.mark_changed(point);
+        return &mut self.fish_stock[Self::segment_at(point)];
+    }
+
+    pub fn trap_at(&self, point: (u16, u16)) -> Option<usize> {
+        return self.traps.iter().position(|trap| matches!(trap, Some(trap) if trap.position == point));
+    }
+
+    /// Traps the player has out, including the ones in chunks that aren't loaded.
+    pub fn trap_count(&self) -> usize {
+        let saved = self.saved_chunks.iter().map(|chunk| chunk.traps.iter().flatten().count()).sum::<usize>();
+        return self.traps.iter().flatten().count() + saved;
+    }
+
+    /// Places a trap in a free slot, returns false if every trap is already in use.
+    pub fn place_trap(&mut self, trap: Trap) -> bool {
+        if self.trap_count() >= MAX_TRAPS {
+            return false;
+        }
+        let Some(slot) = self.traps.iter_mut().find(|slot| slot.is_none()) else {
+            return false;
+        };
+        *slot = Some(trap);
+        self.mark_placed(trap.position);
+        return true;
+    }
+
+    pub fn remove_trap(&mut self, index: usize) -> Option<Trap> {
+        let trap = self.traps[index].take()?;
+        self.mark_removed(trap.position);
+        return Some(trap);
+    }
+
+    pub fn building_at(&self, point: (u16, u16)) -> Option<usize> {
+        return self.buildings.iter().position(|building| matches!(building, Some(building) if building.covers(point)));
+    }
+
+    /// Buildings the player has put up, including the ones in chunks that aren't loaded.
+    pub fn building_count(&self) -> usize {
+        let saved = self.saved_chunks.iter().map(|chunk| chunk.buildings.iter().flatten().count()).sum::<usize>();
+        return self.buildings.iter().flatten().count() + saved;
+    }
+
+    /// Whether a building fits where it is: on the right terrain, clear of everything else, `dry` included, and within a
+    /// single chunk with room to save it.
+    pub fn can_build(&self, building: &Building, dry: (u16, u16)) -> bool {
+        let (_, height) = building.kind.footprint();
+        let (x, y) = (building.position.0 as usize, building.position.1 as usize);
+        if y / CHUNK_HEIGHT != (y + height as usize - 1) / CHUNK_HEIGHT || y >= MAPHEIGHT || !self.has_room_for((x, y)) {
+            return false;
+        }
+        let mut on_shore = false;
+        for tile in building.tiles() {
+            let point = (tile.0 as usize, tile.1 as usize);
+            if !Grid::<Terrain>::in_bounds(point) || !building.kind.allows(self.get_terrain(point)) {
+                return false;
+            }
+            let taken = self.get_bridge_point(point) || self.tree_at(tile).is_some() || self.rock_at(tile).is_some() || self.has_campfire(tile) || self.trap_at(tile).is_some() || self.building_at(tile).is_some();
+            if taken || tile == dry {
+                return false;
+            }
+            let sides = autotile::NORTH | autotile::EAST | autotile::SOUTH | autotile::WEST;
+            on_shore |= self.neighbours(point, |neighbour| !self.get_terrain(neighbour).is_water()) & sides != 0;
+        }
+        return on_shore || !building.kind.needs_shore();
+    }
+
+    /// Puts up a building in a free slot, returns false if every building is already in use.
+    pub fn place_building(&mut self, building: Building) -> bool {
+        if self.building_count() >= MAX_BUILDINGS {
+            return false;
+        }
+        let Some(slot) = self.buildings.iter_mut().find(|slot| slot.is_none()) else {
+            return false;
+        };
+        *slot = Some(building);
+        self.mark_placed(building.position);
+        return true;
+    }
+
+    pub fn remove_building(&mut self, index: usize) -> Option<Building> {
+        let building = self.buildings[index].take()?;
+        self.mark_removed(building.position);
+        return Some(building);
+    }
+
+    /// Index of the thing floating at a point, if there is one.
+    pub fn drifting_at(&self, point: (u16, u16)) -> Option<usize> {
+        return self.drifting.iter().position(|drifting| matches!(drifting, Some(drifting) if drifting.position == point));
+    }
+
+    /// Things floating on the river, including the ones in chunks that aren't loaded.
+    pub fn drifting_count(&self) -> usize {
+        let saved = self.saved_chunks.iter().map(|chunk| chunk.drifting.iter().flatten().count()).sum::<usize>();
+        return self.drifting.iter().flatten().count() + saved;
+    }
+
+    /// Whether something can float onto a point: water with nothing built on it or already floating there.
+    fn is_open_water(&self, point: (usize, usize)) -> bool {
+        let tile = (point.0 as u16, point.1 as u16);
+        return self.get_terrain(point).is_water() && !self.get_bridge_point(point) && self.building_at(tile).is_none() && self.trap_at(tile).is_none() && self.drifting_at(tile).is_none();
+    }
+
+    /// Water flowing past one of the sides of a point, where something dropped from it gets carried off.
+    pub fn flowing_neighbour(&self, point: (u16, u16)) -> Option<(u16, u16)> {
+        return Self::sides((point.0 as usize, point.1 as usize)).into_iter()
+            .find(|&neighbour| self.current(neighbour) != (0, 0) && self.is_open_water(neighbour))
+            .map(|(x, y)| (x as u16, y as u16));
+    }
+
+    /// Puts something on the water in a free slot, returns false if there's no room for it there or anywhere.
+    pub fn set_adrift(&mut self, drifting: Drifting) -> bool {
+        let point = (drifting.position.0 as usize, drifting.position.1 as usize);
+        if !self.is_open_water(point) || self.drifting_count() >= MAX_DRIFTING || !self.has_room_for(point) {
+            return false;
+        }
+        let Some(slot) = self.drifting.iter_mut().find(|slot| slot.is_none()) else {
+            return false;
+        };
+        *slot = Some(drifting);
+        self.mark_placed(drifting.position);
+        return true;
+    }
+
+    /// Fishes something out of the water.
+    pub fn take_drifting(&mut self, index: usize) -> Option<Drifting> {
+        let drifting = self.drifting[index].take()?;
+        self.mark_removed(drifting.position);
+        return Some(drifting);
+    }
+
+    /// Where the current carries something floating on a point, `None` if it stays put: on still water, or when it's
+    /// held back by a bridge, building, trap or the bank. Whatever runs into the bank or an island slips past it on
+    /// either side, and bends too sharp to follow diagonally are taken a tile at a time.
+    pub fn drift_target(&self, point: (usize, usize)) -> Option<(usize, usize)> {
+        let (dx, dy) = self.current(point);
+        if (dx, dy) == (0, 0) || !self.get_terrain(point).is_water() {
+            return None;
+        }
+        return [(dx, dy), (0, dy), (-1, dy), (1, dy), (dx, 0)].into_iter()
+            .filter(|&step| step != (0, 0))
+            .map(|(dx, dy)| (point.0.wrapping_add_signed(dx as isize), point.1.wrapping_add_signed(dy as isize)))
+            .find(|&next| self.is_open_water(next));
+    }
+
+    /// Carries everything floating a tile further downstream, called once every step.
+    pub fn drift(&mut self) {
+        for index in 0..MAX_DRIFTING {
+            let Some(drifting) = self.drifting[index] else {
+                continue;
+            };
+            let point = (drifting.position.0 as usize, drifting.position.1 as usize);
+            // it waits at the edge of a chunk there's no room to save it in
+            let target = self.drift_target(point).filter(|&next| next.1 / CHUNK_HEIGHT == point.1 / CHUNK_HEIGHT || self.has_room_for(next));
+            if let Some((x, y)) = target {
+                self.drifting[index] = Some(Drifting { position: (x as u16, y as u16), ..drifting });
+                if y / CHUNK_HEIGHT != point.1 / CHUNK_HEIGHT {
+                    self.mark_placed((x as u16, y as u16));
+                    self.mark_removed(drifting.position);
+                }
+            }
+        }
+    }
+
+    /// The points above, right of, below and left of a point. Off the left or top they wrap around and end up out of
+    /// bounds.
+    fn sides((x, y): (usize, usize)) -> [(usize, usize); 4] {
+        return [(x, y.wrapping_sub(1)), (x + 1, y), (x, y + 1), (x.wrapping_sub(1), y)];
+    }
+
+    /// Every point the player can walk over without a bridge.
+    fn passable(&self) -> Grid<bool> {
+        let mut passable = Grid::default();
+        for y in 0..MAPHEIGHT {
+            for x in 0..MAPWIDTH {
+                passable.set((x, y), self.get_terrain((x, y)).move_cooldown().is_some());
+            }
+        }
+        let trees = self.trees.iter().flatten().filter(|tree| tree.stage.blocks_movement()).map(|tree| tree.position);
+        let rocks = self.rocks.iter().flatten().filter(|rock| rock.blocks_movement()).map(|rock| rock.position);
+        let buildings = self.buildings.iter().flatten().filter(|building| !building.kind.is_walkable()).flat_map(|building| building.tiles());
+        for (x, y) in trees.chain(rocks).chain(buildings) {
+            passable.set((x as usize, y as usize), false);
+        }
+        return passable;
+    }
+
+    /// Whether a new game can start on a point: dry land that's free to stand on.
+    fn can_spawn_on(&self, point: (usize, usize)) -> bool {
+        let tile = (point.0 as u16, point.1 as u16);
+        let terrain = self.get_terrain(point);
+        let taken = self.tree_at(tile).is_some() || self.rock_at(tile).is_some() || self.building_at(tile).is_some();
+        return Grid::<Terrain>::in_bounds(point) && !terrain.is_water() && terrain.move_cooldown().is_some() && !taken;
+    }
+
+    /// Every point that can be walked to from `from` without a bridge.
+    pub fn reachable(&self, from: (usize, usize)) -> Grid<bool> {
+        let passable = self.passable();
+        let mut reached = Grid::default();
+        reached.set(from, true);
+        let mut spreading = true;
+        let mut backwards = false;
+        while spreading {
+            spreading = false;
+            // sweeping the other way every time lets it spread up and left as quickly as down and right
+            for i in 0..MAPWIDTH * MAPHEIGHT {
+                let i = if backwards { MAPWIDTH * MAPHEIGHT - 1 - i } else { i };
+                let point = (i % MAPWIDTH, i / MAPWIDTH);
+                if reached.get(point) || !passable.get(point) {
+                    continue;
+                }
+                if Self::sides(point).into_iter().any(|side| reached.get(side)) {
+                    reached.set(point, true);
+                    spreading = true;
+                }
+            }
+            backwards = !backwards;
+        }
+        return reached;
+    }
+
+    /// A tree that gives wood and can be cut from one of the reached points.
+    fn reachable_tree(&self, reached: &Grid<bool>) -> Option<(u16, u16)> {
+        return self.trees.iter().flatten()
+            .find(|tree| tree.stage.wood(false) > 0 && Self::sides((tree.position.0 as usize, tree.position.1 as usize)).into_iter().any(|side| reached.get(side)))
+            .map(|tree| tree.position);
+    }
+
+    /// The land closest to `SPAWN_ORIGIN` that a tree can be reached from, `None` if no tree can be reached at all.
+    pub fn find_spawn(&self) -> Option<Spawn> {
+        // a patch of land that can't reach a tree from one point can't from any other
+        let mut rejected: Grid<bool> = Grid::default();
+        for point in Self::spawn_candidates() {
+            if rejected.get(point) || !self.can_spawn_on(point) {
+                continue;
+            }
+            let reached = self.reachable(point);
+            if let Some(tree) = self.reachable_tree(&reached) {
+                return Some(Spawn { position: (point.0 as u16, point.1 as u16), tree });
+            }
+            for y in 0..MAPHEIGHT {
+                for x in 0..MAPWIDTH {
+                    if reached.get((x, y)) {
+                        rejected.set((x, y), true);
+                    }
+                }
+            }
+        }
+        return None;
+    }
+
+    /// Plants a tree next to the land closest to `SPAWN_ORIGIN`, for worlds where no tree can be reached on foot.
+    fn plant_spawn(&mut self) -> Option<Spawn> {
+        for point in Self::spawn_candidates() {
+            if !self.can_spawn_on(point) {
+                continue;
+            }
+            let Some(side) = Self::sides(point).into_iter().find(|&side| self.can_spawn_on(side)) else {
+                continue;
+            };
+            let chunk = side.1 / CHUNK_HEIGHT;
+            let Some(slot) = self.trees[chunk * TREES_PER_CHUNK..(chunk + 1) * TREES_PER_CHUNK].iter_mut().find(|slot| slot.is_none()) else {
+                continue;
+            };
+            let tree = (side.0 as u16, side.1 as u16);
+            *slot = Some(Tree::new(tree, GrowthStage::Mature));
+            self.mark_changed(tree);
+            return Some(Spawn { position: (point.0 as u16, point.1 as u16), tree });
+        }
+        return None;
+    }
+
+    /// Points a new game may start on, ring by ring outwards from `SPAWN_ORIGIN`.
+    fn spawn_candidates() -> impl Iterator<Item = (usize, usize)> {
+        let (x, y) = (SPAWN_ORIGIN.0 as isize, SPAWN_ORIGIN.1 as isize);
+        return (0..MAPWIDTH as isize)
+            .flat_map(|range| (-range..=range).flat_map(move |dy| (-range..=range).map(move |dx| (dx, dy, range))))
+            .filter(|&(dx, dy, range)| dx.abs() == range || dy.abs() == range)
+            .map(move |(dx, dy, _)| ((x + dx) as usize, (y + dy) as usize))
+            .filter(|&(x, y)| x < MAPWIDTH && y < SPAWN_ROWS);
+    }
+
+    /// Whether a spawn keeps its promise: the player can stand on it and walk up to its tree without a bridge.
+    pub fn check_spawn(&self, spawn: &Spawn) -> bool {
+        let position = (spawn.position.0 as usize, spawn.position.1 as usize);
+        if !self.can_spawn_on(position) || !matches!(self.tree_at(spawn.tree), Some(tree) if tree.stage.wood(false) > 0) {
+            return false;
+        }
+        let reached = self.reachable(position);
+        return Self::sides((spawn.tree.0 as usize, spawn.tree.1 as usize)).into_iter().any(|side| reached.get(side));
+    }
+
+    /// Traps left on dry land after a flood recedes, or stuck in the ice, don't catch anything.
+    pub fn tick_traps(&mut self, frozen: bool) {
+        if frozen {
+            return;
+        }
+        for index in 0..MAX_TRAPS {
+            let Some(position) = self.traps[index].map(|trap| trap.position) else {
+                continue;
+            };
+            if self.get_terrain((position.0 as usize, position.1 as usize)).is_water() {
+                let stock = &mut self.fish_stock[Self::segment_at(position)];
+                if let Some(trap) = self.traps[index].as_mut() {
+                    trap.tick(stock);
+                }
+            }
+        }
+    }
+
+    /// There's only ever one campfire, building a new one abandons the old one even if it's far away.
+    pub fn set_campfire(&mut self, campfire: Campfire) {
+        for chunk in self.saved_chunks.iter_mut() {
+            chunk.campfire = None;
+        }
+        self.count_saved_placed();
+        if let Some(old) = self.campfire.replace(campfire) {
+            self.mark_removed(old.position);
+        }
+        self.mark_placed(campfire.position);
+    }
+
+    pub fn has_campfire(&self, point: (u16, u16)) -> bool {
+        return matches!(self.campfire, Some(campfire) if campfire.position == point);
+    }
+
+    pub fn has_fruit(&self, index: usize) -> bool {
+        return matches!(self.trees[index], Some(tree) if tree.stage == GrowthStage::Mature) && self.tree_fruit[index] == Some(0);
+    }
+
+    /// Tree of any size standing on a point.
+    pub fn tree_at(&self, point: (u16, u16)) -> Option<&Tree> {
+        return self.trees.iter().flatten().find(|tree| tree.position == point);
+    }
+
+    /// Whether a tree blocks the way on a point.
+    pub fn has_tree(&self, point: (u16, u16)) -> bool {
+        return self.tree_at(point).is_some_and(|tree| tree.stage.blocks_movement());
+    }
+
+    /// Rock standing on a point, or the rubble left of one.
+    pub fn rock_at(&self, point: (u16, u16)) -> Option<usize> {
+        return self.rocks.iter().position(|rock| matches!(rock, Some(rock) if rock.position == point));
+    }
+
+    /// Whether a rock blocks the way on a point.
+    pub fn has_rock(&self, point: (u16, u16)) -> bool {
+        return self.rock_at(point).is_some_and(|index| self.rocks[index].is_some_and(|rock| rock.blocks_movement()));
+    }
+
+    /// Grows the trees that aren't fully grown yet, and lets fruit grow back on the ones that are. Called once every step.
+    /// None grows into the way at `dry`, where the player stands.
+    pub fn tick_trees(&mut self, dry: (u16, u16)) {
+        for index in 0..TREECOUNT {
+            let Some(tree) = self.trees[index].as_mut() else {
+                continue;
+            };
+            if tree.stage != GrowthStage::Mature {
+                tree.tick(tree.position == dry);
+            } else if let Some(fruit_timeout) = self.tree_fruit[index].filter(|&timeout| timeout > 0) {
+                // fruit only grows back on standing trees
+                self.tree_fruit[index] = Some(fruit_timeout - 1);
+            } else {
+                continue;
+            }
+            self.dirty[index / TREES_PER_CHUNK] = true;
+        }
+    }
+
+    /// Lets mined out rocks come back, though never under `dry`, where the player stands.
+    pub fn tick_rocks(&mut self, dry: (u16, u16)) {
+        for rock in self.rocks.iter_mut().flatten() {
+            rock.tick(rock.position == dry);
+        }
+    }
+
+    /// Lets grown trees drop a seed now and then, it sprouts into a sapling on free grass nearby as long as its chunk has
+    /// a tree slot left. Fruit trees seed more fruit trees.
+    pub fn spread_trees(&mut self, dry: (u16, u16), rng: &mut SplitMix64) {
+        for index in 0..TREECOUNT {
+            let Some(Tree { position: (x, y), stage: GrowthStage::Mature, .. }) = self.trees[index] else {
+                continue;
+            };
+            if rng.gen::<u16>() % SEED_CHANCE != 0 {
+                continue;
+            }
+            let spot = (x.wrapping_add_signed(rng.gen_range(-SEED_RANGE..=SEED_RANGE)), y.wrapping_add_signed(rng.gen_range(-SEED_RANGE..=SEED_RANGE)));
+            let point = (spot.0 as usize, spot.1 as usize);
+            let taken = self.tree_at(spot).is_some() || self.rock_at(spot).is_some() || self.building_at(spot).is_some();
+            if !Grid::<Terrain>::in_bounds(point) || self.get_terrain(point) != Terrain::Grass || self.get_bridge_point(point) || self.has_campfire(spot) || taken || spot == dry {
+                continue;
+            }
+            let slots = point.1 / CHUNK_HEIGHT * TREES_PER_CHUNK..(point.1 / CHUNK_HEIGHT + 1) * TREES_PER_CHUNK;
+            if let Some(free) = slots.into_iter().find(|&slot| self.trees[slot].is_none()) {
+                self.trees[free] = Some(Tree::new(spot, GrowthStage::Sapling));
+                self.tree_fruit[free] = self.tree_fruit[index].map(|_| 0);
+                self.mark_changed(spot);
+            }
+        }
+    }
+}
@@ -0,0 +1,78 @@
+use serde::{Deserialize, Serialize};
+
+/// Steps a mined out rock takes to come back, ore takes longer to build up again.
+const BOULDER_RESPAWN_STEPS: u8 = 90;
+const ORE_RESPAWN_STEPS: u8 = 180;
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub enum RockKind {
+    Boulder,
+    /// A rock shot through with ore, rarer and worth more stone.
+    Ore,
+}
+
+impl RockKind {
+    /// Stone given by mining it.
+    pub fn stone(&self) -> u8 {
+        match self {
+            RockKind::Boulder => 2,
+            RockKind::Ore => 4,
+        }
+    }
+
+    fn respawn_steps(&self) -> u8 {
+        match self {
+            RockKind::Boulder => BOULDER_RESPAWN_STEPS,
+            RockKind::Ore => ORE_RESPAWN_STEPS,
+        }
+    }
+}
+
+/// A rock or ore deposit, mined with a pickaxe. Once mined out it leaves rubble behind until it comes back.
+#[derive(Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Rock {
+    pub position: (u16, u16),
+    pub kind: RockKind,
+    /// Steps until it comes back, 0 while it's standing.
+    respawn: u8,
+}
+
+impl Rock {
+    pub fn new(position: (u16, u16), kind: RockKind) -> Self {
+        Self { position, kind, respawn: 0 }
+    }
+
+    pub fn is_mined(&self) -> bool {
+        return self.respawn > 0;
+    }
+
+    /// Whether the player has to walk around it, rubble can be walked over.
+    pub fn blocks_movement(&self) -> bool {
+        return !self.is_mined();
+    }
+
+    /// Mines it out, returning the stone it gave, none if there's only rubble left.
+    pub fn mine(&mut self) -> u8 {
+        if self.is_mined() {
+            return 0;
+        }
+        self.respawn = self.kind.respawn_steps();
+        return self.kind.stone();
+    }
+
+    /// Called once every step, counts down to it coming back. It waits for the player to step off the rubble first.
+    pub fn tick(&mut self, stood_on: bool) {
+        if self.respawn > 1 || !stood_on {
+            self.respawn = self.respawn.saturating_sub(1);
+        }
+    }
+
+    /// Frame in `rock.aseprite`.
+    pub fn frame(&self) -> usize {
+        match self.kind {
+            _ if self.is_mined() => 2,
+            RockKind::Boulder => 0,
+            RockKind::Ore => 1,
+        }
+    }
+}
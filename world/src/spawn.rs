@@ -0,0 +1,8 @@
+/// Where a new game starts: land the player can stand on, with a tree they can walk up to and cut without building a
+/// bridge first. `MapData::gen` always finds one, and `MapData::check_spawn` confirms it holds.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub struct Spawn {
+    pub position: (u16, u16),
+    /// The tree that can be reached from there.
+    pub tree: (u16, u16),
+}
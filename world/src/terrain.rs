@@ -0,0 +1,64 @@
+/// What a map tile is made of, the river is deep in the middle and gets shallower towards its banks.
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Default)]
+pub enum Terrain {
+    #[default]
+    Grass,
+    SandBank,
+    ShallowWater,
+    DeepWater,
+    Rock,
+    Mud,
+}
+
+impl Terrain {
+    pub fn is_water(&self) -> bool {
+        return matches!(self, Terrain::ShallowWater | Terrain::DeepWater);
+    }
+
+    /// Frames the player has to wait after stepping onto this terrain, `None` if it can't be walked on at all.
+    pub fn move_cooldown(&self) -> Option<u8> {
+        match self {
+            Terrain::Grass | Terrain::SandBank => Some(0),
+            Terrain::Mud => Some(8),
+            Terrain::ShallowWater => Some(16),
+            Terrain::DeepWater | Terrain::Rock => None,
+        }
+    }
+
+    /// Percentage applied to bite chances, the big fish stay in deep water.
+    pub fn bite_modifier(&self) -> u8 {
+        match self {
+            Terrain::DeepWater => 120,
+            Terrain::ShallowWater => 60,
+            Terrain::Grass | Terrain::SandBank | Terrain::Rock | Terrain::Mud => 0,
+        }
+    }
+
+    /// Orders the river's bands from dry land up to deep water, so overlapping features keep the wettest terrain.
+    pub fn wetness(&self) -> u8 {
+        match self {
+            Terrain::DeepWater => 3,
+            Terrain::ShallowWater => 2,
+            Terrain::SandBank => 1,
+            Terrain::Grass | Terrain::Rock | Terrain::Mud => 0,
+        }
+    }
+
+    /// Whether rain can flood it.
+    pub fn can_flood(&self) -> bool {
+        return matches!(self, Terrain::Grass | Terrain::SandBank | Terrain::Mud);
+    }
+
+    /// Index of the tile drawn for it in `map.aseprite`, water next to land is drawn with the shore tiles instead.
+    pub fn tile(&self, frozen: bool) -> usize {
+        match self {
+            Terrain::ShallowWater | Terrain::DeepWater if frozen => 4,
+            Terrain::SandBank => 0,
+            Terrain::DeepWater => 1,
+            Terrain::Grass => 5,
+            Terrain::ShallowWater => 6,
+            Terrain::Rock => 7,
+            Terrain::Mud => 8,
+        }
+    }
+}
@@ -0,0 +1,59 @@
+use serde::{Deserialize, Serialize};
+use crate::fishstock::FishStock;
+
+pub const MAX_TRAPS: usize = 4;
+
+#[derive(Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TrapKind {
+    FishTrap,
+    Net,
+}
+
+impl TrapKind {
+    pub fn capacity(&self) -> u8 {
+        match self {
+            TrapKind::FishTrap => 3,
+            TrapKind::Net => 5,
+        }
+    }
+
+    pub fn steps_per_fish(&self) -> u8 {
+        match self {
+            TrapKind::FishTrap => 30,
+            TrapKind::Net => 20,
+        }
+    }
+}
+
+#[derive(Copy, Clone, Serialize, Deserialize)]
+pub struct Trap {
+    pub position: (u16, u16),
+    pub kind: TrapKind,
+    pub fish: u8,
+    steps: u8,
+}
+
+impl Trap {
+    pub fn new(position: (u16, u16), kind: TrapKind) -> Self {
+        Self { position, kind, fish: 0, steps: 0 }
+    }
+
+    pub fn is_full(&self) -> bool {
+        return self.fish >= self.kind.capacity();
+    }
+
+    /// Called once every step, traps catch out of the stock of the river stretch they're placed in.
+    pub fn tick(&mut self, stock: &mut FishStock) {
+        if self.is_full() {
+            return;
+        }
+        self.steps += 1;
+        if self.steps >= self.kind.steps_per_fish() {
+            self.steps = 0;
+            if stock.density() > 0 {
+                stock.take();
+                self.fish += 1;
+            }
+        }
+    }
+}
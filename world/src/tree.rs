@@ -0,0 +1,89 @@
+use serde::{Deserialize, Serialize};
+
+/// Steps a tree spends in every stage before growing into the next one.
+const STEPS_PER_STAGE: u8 = 15;
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub enum GrowthStage {
+    /// What's left after cutting a tree down, a new tree sprouts from it.
+    Stump,
+    Sapling,
+    Young,
+    Mature,
+}
+
+impl GrowthStage {
+    pub fn next(&self) -> Option<GrowthStage> {
+        match self {
+            GrowthStage::Stump => Some(GrowthStage::Sapling),
+            GrowthStage::Sapling => Some(GrowthStage::Young),
+            GrowthStage::Young => Some(GrowthStage::Mature),
+            GrowthStage::Mature => None,
+        }
+    }
+
+    /// Wood planks given by cutting it down, an axe gets more out of a standing tree.
+    pub fn wood(&self, axe: bool) -> u8 {
+        match self {
+            GrowthStage::Stump => 1,
+            GrowthStage::Sapling => 0,
+            GrowthStage::Young if axe => 3,
+            GrowthStage::Young => 2,
+            GrowthStage::Mature if axe => 5,
+            GrowthStage::Mature => 3,
+        }
+    }
+
+    /// Whether the player has to walk around it, smaller trees can be walked through.
+    pub fn blocks_movement(&self) -> bool {
+        return *self == GrowthStage::Mature;
+    }
+
+    /// Frame in `tree.aseprite`, fruit trees show their fruit in the second frame once it's grown.
+    pub fn frame(&self, fruit: bool) -> usize {
+        match self {
+            GrowthStage::Mature if fruit => 1,
+            GrowthStage::Mature => 0,
+            GrowthStage::Stump => 2,
+            GrowthStage::Sapling => 3,
+            GrowthStage::Young => 4,
+        }
+    }
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Tree {
+    pub position: (u16, u16),
+    pub stage: GrowthStage,
+    /// Steps until it grows into the next stage.
+    steps: u8,
+}
+
+impl Tree {
+    pub fn new(position: (u16, u16), stage: GrowthStage) -> Self {
+        Self { position, stage, steps: STEPS_PER_STAGE }
+    }
+
+    /// Cuts the tree down to a stump, or digs out a stump, returning the wood it gave and what's left of it.
+    pub fn cut(&self, axe: bool) -> (u8, Option<Tree>) {
+        let wood = self.stage.wood(axe);
+        if self.stage == GrowthStage::Stump {
+            return (wood, None);
+        }
+        return (wood, Some(Tree::new(self.position, GrowthStage::Stump)));
+    }
+
+    /// Called once every step, grows the tree into its next stage once it's had the time to. It waits for the player to
+    /// step off before growing into a stage that blocks them.
+    pub fn tick(&mut self, stood_on: bool) {
+        let Some(next) = self.stage.next() else {
+            return;
+        };
+        if self.steps > 1 || !stood_on || !next.blocks_movement() {
+            self.steps = self.steps.saturating_sub(1);
+        }
+        if self.steps == 0 {
+            *self = Tree::new(self.position, next);
+        }
+    }
+}
@@ -0,0 +1,181 @@
+use serde::{Deserialize, Serialize};
+use crate::chunk::TREES_PER_CHUNK;
+
+/// Percent chances of every chunk getting each extra feature around the main river.
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub struct RiverFeatures {
+    /// A smaller stream flowing into the river from the side of the map.
+    pub tributary_chance: u8,
+    /// A pond or lake away from the river.
+    pub lake_chance: u8,
+    /// A crescent of water left behind next to the river by an old bend.
+    pub oxbow_chance: u8,
+    /// The river splits around a piece of land that can only be reached over a bridge.
+    pub island_chance: u8,
+}
+
+impl RiverFeatures {
+    pub const DEFAULT: RiverFeatures = RiverFeatures {
+        tributary_chance: 35,
+        lake_chance: 30,
+        oxbow_chance: 20,
+        island_chance: 25,
+    };
+}
+
+impl Default for RiverFeatures {
+    fn default() -> Self {
+        return Self::DEFAULT;
+    }
+}
+
+/// Every number a world is generated from, chosen through a `WorldPreset` on the new game menu and kept in the save
+/// so the same seed keeps giving the same world.
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub struct WorldGenParams {
+    /// How far either side of the middle of the map the river may cross from one chunk into the next.
+    pub river_wander: u8,
+    /// How far every point the river is drawn through may stray from halfway between its neighbours.
+    pub midpoint_noise: u8,
+    /// Distance from the river's centerline where each band of terrain ends, no deep water at all if `None`.
+    pub deep_water_range: Option<u8>,
+    pub shallow_water_range: u8,
+    pub sand_bank_range: u8,
+    pub mud_range: u8,
+    /// Forests generated in every chunk, how many trees each one tries to grow and how far they're spread from the
+    /// middle, the ranges are inclusive.
+    pub forest_clusters: (u8, u8),
+    pub forest_size: (u8, u8),
+    pub forest_radius: u8,
+    pub lone_trees: (u8, u8),
+    /// Most trees a chunk is generated with, at most `TREES_PER_CHUNK`. The slots left over are for saplings.
+    pub max_trees: u8,
+    /// Rocks generated in every chunk, inclusive and at most `ROCKS_PER_CHUNK`, and the percent chance of each being ore.
+    pub rocks: (u8, u8),
+    pub ore_chance: u8,
+    pub features: RiverFeatures,
+}
+
+impl WorldGenParams {
+    pub const DEFAULT: WorldGenParams = WorldGenParams {
+        river_wander: 6,
+        midpoint_noise: 4,
+        deep_water_range: Some(1),
+        shallow_water_range: 2,
+        sand_bank_range: 4,
+        mud_range: 7,
+        forest_clusters: (1, 3),
+        forest_size: (5, 10),
+        forest_radius: 3,
+        lone_trees: (1, 3),
+        max_trees: TREES_PER_CHUNK as u8,
+        rocks: (1, 3),
+        ore_chance: 25,
+        features: RiverFeatures::DEFAULT,
+    };
+}
+
+impl Default for WorldGenParams {
+    fn default() -> Self {
+        return Self::DEFAULT;
+    }
+}
+
+/// Bits of a shared seed that seed the world, the hex digit above them picks the preset.
+const SEED_BITS: u32 = 28;
+pub const SEED_MASK: u32 = (1 << SEED_BITS) - 1;
+
+/// Kinds of world offered on the new game menu.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum WorldPreset {
+    River,
+    NarrowCreek,
+    WideRiver,
+    DenseForest,
+}
+
+impl WorldPreset {
+    pub const ALL: [WorldPreset; 4] = [WorldPreset::River, WorldPreset::NarrowCreek, WorldPreset::WideRiver, WorldPreset::DenseForest];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            WorldPreset::River => "River",
+            WorldPreset::NarrowCreek => "Narrow creek",
+            WorldPreset::WideRiver => "Wide river",
+            WorldPreset::DenseForest => "Dense forest",
+        }
+    }
+
+    pub fn params(&self) -> WorldGenParams {
+        match self {
+            WorldPreset::River => WorldGenParams::DEFAULT,
+            // shallow enough to wade through in most places, winding between plenty of trees and rocks
+            WorldPreset::NarrowCreek => WorldGenParams {
+                river_wander: 10,
+                midpoint_noise: 6,
+                deep_water_range: None,
+                shallow_water_range: 1,
+                sand_bank_range: 2,
+                mud_range: 5,
+                forest_clusters: (2, 4),
+                rocks: (2, 5),
+                features: RiverFeatures {
+                    tributary_chance: 50,
+                    lake_chance: 20,
+                    oxbow_chance: 10,
+                    island_chance: 10,
+                },
+                ..WorldGenParams::DEFAULT
+            },
+            // hard to cross without a long bridge, with little room for forests along the banks
+            WorldPreset::WideRiver => WorldGenParams {
+                river_wander: 3,
+                midpoint_noise: 3,
+                deep_water_range: Some(3),
+                shallow_water_range: 5,
+                sand_bank_range: 7,
+                mud_range: 10,
+                rocks: (0, 2),
+                features: RiverFeatures {
+                    tributary_chance: 25,
+                    lake_chance: 15,
+                    oxbow_chance: 30,
+                    island_chance: 45,
+                },
+                ..WorldGenParams::DEFAULT
+            },
+            WorldPreset::DenseForest => WorldGenParams {
+                forest_clusters: (3, 5),
+                forest_size: (8, 14),
+                forest_radius: 4,
+                lone_trees: (2, 5),
+                ..WorldGenParams::DEFAULT
+            },
+        }
+    }
+
+    /// The preset after this one on the menu, wrapping around.
+    pub fn next(&self) -> WorldPreset {
+        return Self::ALL[(self.index() + 1) % Self::ALL.len()];
+    }
+
+    fn index(&self) -> usize {
+        return Self::ALL.iter().position(|preset| preset == self).unwrap_or(0);
+    }
+
+    /// The preset a world was generated from, if its numbers came from one.
+    pub fn of(params: &WorldGenParams) -> Option<WorldPreset> {
+        return Self::ALL.into_iter().find(|preset| preset.params() == *params);
+    }
+
+    /// The seed shown to share a world, with this preset folded into its first hex digit.
+    pub fn share_seed(&self, seed: u64) -> u32 {
+        return (self.index() as u32) << SEED_BITS | (seed as u32 & SEED_MASK);
+    }
+
+    /// The preset and world seed a shared seed stands for.
+    pub fn from_shared_seed(shared: u32) -> (WorldPreset, u64) {
+        let preset = Self::ALL[(shared >> SEED_BITS) as usize % Self::ALL.len()];
+        return (preset, (shared & SEED_MASK) as u64);
+    }
+}
@@ -0,0 +1,16 @@
+use rsim_world::map::MapData;
+use rsim_world::worldgen::{WorldPreset, SEED_MASK};
+
+/// Seeds tried for every preset, spread over the whole range a shared seed can hold.
+const SEEDS: u64 = 1024;
+
+#[test]
+fn every_preset_spawns_next_to_a_reachable_tree() {
+    for preset in WorldPreset::ALL {
+        for step in 0..SEEDS {
+            let seed = step * (SEED_MASK as u64 / SEEDS);
+            let (map, spawn) = MapData::gen(seed, preset.params());
+            assert!(map.check_spawn(&spawn), "{} seed {} spawned at {:?}", preset.name(), seed, spawn.position);
+        }
+    }
+}